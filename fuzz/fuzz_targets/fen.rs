@@ -0,0 +1,21 @@
+#![no_main]
+
+use chess_model::board::Board;
+use libfuzzer_sys::fuzz_target;
+
+// Any string: parsing must not panic, and a parsed board must be usable
+fuzz_target!(|fen: &str| {
+    let board = match Board::from_fen(fen) {
+        Ok(board) => board,
+        Err(_) => return,
+    };
+
+    assert!(board == board.clone());
+    assert_eq!(board.position_hash(), board.fork().position_hash());
+
+    for (from, to) in board.move_gen(None, &[]) {
+        board.is_legal(&from, &to);
+    }
+    board.checkers();
+    board.to_string();
+});
@@ -0,0 +1,46 @@
+#![no_main]
+
+use chess_model::{board::Board, piece::PieceType};
+use libfuzzer_sys::fuzz_target;
+
+const POSITIONS: [&str; 4] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 3+3 0 1",
+];
+
+// The first byte picks a position, every other byte one of its legal moves.
+// Moves must be accepted by push, never leave the king in check nor the board
+// inconsistent, and popping them all must give back the starting position.
+fuzz_target!(|data: &[u8]| {
+    let (first, picks) = match data.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+
+    let mut board = Board::from_fen(POSITIONS[*first as usize % POSITIONS.len()]).unwrap();
+    let start = board.clone();
+
+    for pick in picks {
+        let moves: Vec<_> = board
+            .move_gen(None, &[])
+            .filter(|(from, to)| board.is_legal(from, to))
+            .collect();
+        if moves.is_empty() {
+            break;
+        }
+
+        let color = board.info.turn;
+        let (from, to) = moves[*pick as usize % moves.len()];
+        if board.push(&from, &to, None).is_err() {
+            board.push(&from, &to, Some(PieceType::Queen)).unwrap();
+        }
+        assert!(!board.is_in_check(color));
+        board.assert_consistent();
+    }
+
+    while board.pop().is_some() {}
+    assert!(board == start);
+    assert_eq!(board.position_hash(), start.position_hash());
+});
@@ -0,0 +1,19 @@
+#![no_main]
+
+use chess_model::board::Board;
+use libfuzzer_sys::fuzz_target;
+
+// Any string as UCI or SAN: either an error, or a move that pop takes back
+fuzz_target!(|text: &str| {
+    let start =
+        Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+
+    for push in [Board::push_uci, Board::push_san] {
+        let mut board = start.clone();
+        if push(&mut board, text).is_ok() {
+            assert!(board.pop().is_some());
+            assert!(board == start);
+        }
+    }
+});
@@ -0,0 +1,115 @@
+use super::{Board, Coord};
+
+/// Default color of arrows and highlights
+pub const ANNOTATION_COLOR: &str = "#15781b";
+
+/// Marks drawn over a board by `to_svg` and `Display`, e.g. the candidate
+/// moves of a search. They are not part of the position: they are ignored by
+/// equality and hashing, and cleared when a move is played or taken back.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Annotations {
+    /// From, to and color of each arrow
+    pub arrows: Vec<(Coord, Coord, String)>,
+    /// Cells filled with a color
+    pub highlights: Vec<(Coord, String)>,
+    /// Short texts written in a cell, e.g. a visit count
+    pub labels: Vec<(Coord, String)>,
+}
+
+impl Annotations {
+    pub fn is_empty(&self) -> bool {
+        self.arrows.is_empty() && self.highlights.is_empty() && self.labels.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl Board {
+    pub fn add_arrow(&mut self, from: Coord, to: Coord, color: &str) {
+        self.annotations.arrows.push((from, to, color.to_string()));
+    }
+
+    pub fn highlight(&mut self, coord: Coord, color: &str) {
+        self.annotations.highlights.push((coord, color.to_string()));
+    }
+
+    pub fn add_label(&mut self, coord: Coord, text: &str) {
+        self.annotations.labels.push((coord, text.to_string()));
+    }
+
+    pub fn clear_annotations(&mut self) {
+        self.annotations.clear();
+    }
+
+    /// Glyph of an empty cell in the text rendering: the first letter of its
+    /// label, `*` if it is highlighted or an arrow points to it, or `·`
+    pub(super) fn annotation_glyph(&self, coord: &Coord) -> String {
+        let annotations = &self.annotations;
+
+        if let Some((_, text)) = annotations.labels.iter().find(|(c, _)| c == coord) {
+            if let Some(c) = text.chars().next() {
+                return c.to_string();
+            }
+        }
+
+        let marked = annotations.highlights.iter().any(|(c, _)| c == coord)
+            || annotations.arrows.iter().any(|(_, to, _)| to == coord);
+        match marked {
+            true => "*".to_string(),
+            false => "·".to_string(),
+        }
+    }
+
+    /// Arrows and labels as text, one per line, e.g. "e2 -> e4" or "e4: 120"
+    pub(super) fn annotation_lines(&self) -> String {
+        let notation = self.notation();
+        let cell = |coord: &Coord| notation.cell_to_str(coord).unwrap_or_default();
+        let mut s = String::new();
+
+        for (from, to, _) in &self.annotations.arrows {
+            s.push_str(&format!("{} -> {}\n", cell(from), cell(to)));
+        }
+        for (coord, text) in &self.annotations.labels {
+            s.push_str(&format!("{}: {}\n", cell(coord), text));
+        }
+
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotations() {
+        let mut board = Board::default();
+        let (e2, e4, d4) = (
+            Coord { row: 6, col: 4 },
+            Coord { row: 4, col: 4 },
+            Coord { row: 4, col: 3 },
+        );
+
+        board.add_arrow(e2, e4, ANNOTATION_COLOR);
+        board.highlight(d4, "#ff0000");
+        board.add_label(e4, "120 visits");
+        assert!(board == Board::default());
+
+        let text = board.to_string();
+        assert!(text.contains("4 · · · * 1 · · · \n"));
+        assert!(text.ends_with("e2 -> e4\ne4: 120 visits\n"));
+
+        let svg = board.to_svg();
+        assert!(svg.contains("#ff0000") && svg.contains("120 visits"));
+        assert_eq!(svg.matches("<line").count(), 1);
+
+        // they describe the current position only
+        board.fork().add_label(d4, "fork");
+        assert_eq!(board.annotations.labels.len(), 1);
+        board.push_uci("e2e4").unwrap();
+        assert!(board.annotations.is_empty());
+        assert!(!board.to_svg().contains("<line"));
+    }
+}
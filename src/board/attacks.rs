@@ -0,0 +1,331 @@
+use super::{Bitboard, Board, Coord};
+use crate::moves::{util::direction_iter, Direction};
+use crate::piece::{Color, Piece, PieceType};
+
+const SIZE: i32 = 8;
+
+const KNIGHT_STEPS: [(i32, i32); 8] = [
+    (-2, -1),
+    (-2, 1),
+    (-1, -2),
+    (-1, 2),
+    (1, -2),
+    (1, 2),
+    (2, -1),
+    (2, 1),
+];
+
+const KING_STEPS: [(i32, i32); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// Attack table of a piece that jumps by `steps`, built at compile time
+const fn leaper_table(steps: &[(i32, i32)]) -> [Bitboard; 64] {
+    let mut table = [Bitboard::EMPTY; 64];
+
+    let mut index = 0;
+    while index < 64 {
+        let (row, col) = (index as i32 / SIZE, index as i32 % SIZE);
+        let mut bits = 0;
+
+        let mut i = 0;
+        while i < steps.len() {
+            let (row, col) = (row + steps[i].0, col + steps[i].1);
+            if row >= 0 && row < SIZE && col >= 0 && col < SIZE {
+                bits |= 1 << (row * SIZE + col);
+            }
+            i += 1;
+        }
+
+        table[index] = Bitboard(bits);
+        index += 1;
+    }
+
+    table
+}
+
+static KNIGHT_ATTACKS: [Bitboard; 64] = leaper_table(&KNIGHT_STEPS);
+static KING_ATTACKS: [Bitboard; 64] = leaper_table(&KING_STEPS);
+// White pawns capture north (row - 1), black pawns south (row + 1)
+static WHITE_PAWN_ATTACKS: [Bitboard; 64] = leaper_table(&[(-1, -1), (-1, 1)]);
+static BLACK_PAWN_ATTACKS: [Bitboard; 64] = leaper_table(&[(1, -1), (1, 1)]);
+
+/// Cells a knight in `coord` attacks on an 8x8 board
+pub fn knight_attacks(coord: &Coord) -> Bitboard {
+    match Bitboard::index(coord) {
+        Some(index) => KNIGHT_ATTACKS[index as usize],
+        None => Bitboard::EMPTY,
+    }
+}
+
+/// Cells a king in `coord` attacks on an 8x8 board (castling is not an attack)
+pub fn king_attacks(coord: &Coord) -> Bitboard {
+    match Bitboard::index(coord) {
+        Some(index) => KING_ATTACKS[index as usize],
+        None => Bitboard::EMPTY,
+    }
+}
+
+/// Cells a `color` pawn in `coord` attacks on an 8x8 board
+pub fn pawn_attacks(coord: &Coord, color: Color) -> Bitboard {
+    let table: &[Bitboard; 64] = match color {
+        Color::White => &WHITE_PAWN_ATTACKS,
+        Color::Black => &BLACK_PAWN_ATTACKS,
+    };
+
+    match Bitboard::index(coord) {
+        Some(index) => table[index as usize],
+        None => Bitboard::EMPTY,
+    }
+}
+
+impl Board {
+    pub(super) fn is_standard_size(&self) -> bool {
+        self.get_rows() == SIZE as u32 && self.get_cols() == SIZE as u32
+    }
+
+    /// Cells of the `by` pieces that attack `coord`.
+    ///
+    /// Whatever is in `coord` is ignored, so a defended piece is attacked by
+    /// its defenders too. Standard pieces on 8x8 boards use the precomputed
+    /// tables and ray walks; custom pieces and other board sizes fall back to
+    /// the pieces' `Move` implementations.
+    pub fn attackers(&self, coord: &Coord, by: Color) -> Vec<Coord> {
+        if !self.in_bounds(coord) {
+            return vec![];
+        }
+
+        let standard = self.is_standard_size();
+        let mut attackers = vec![];
+        let mut fallback = vec![];
+
+        for piece in self.get_all_pieces(&by) {
+            let attacks = match (standard, piece.piece) {
+                (true, PieceType::Knight) => knight_attacks(&piece.coord),
+                (true, PieceType::King) => king_attacks(&piece.coord),
+                (true, PieceType::Pawn) => pawn_attacks(&piece.coord, by),
+                (true, PieceType::Rook | PieceType::Bishop | PieceType::Queen) => continue,
+                _ => {
+                    fallback.push(piece);
+                    continue;
+                }
+            };
+
+            if attacks.contains(coord) {
+                attackers.push(piece.coord);
+            }
+        }
+
+        if standard {
+            attackers.append(&mut self.slider_attackers(coord, by));
+        }
+
+        if !fallback.is_empty() {
+            attackers.append(&mut self.move_attackers(coord, by, &fallback));
+        }
+
+        attackers
+    }
+
+    pub fn is_square_attacked(&self, coord: &Coord, by: Color) -> bool {
+        !self.attackers(coord, by).is_empty()
+    }
+
+    /// Number of `by` pieces attacking each cell, indexed `[row][col]`
+    pub fn attacked_map(&self, by: Color) -> Vec<Vec<u32>> {
+        (0..self.get_rows() as i32)
+            .map(|row| {
+                (0..self.get_cols() as i32)
+                    .map(|col| self.attackers(&Coord { row, col }, by).len() as u32)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The board with the `attacked_map` counts drawn on the empty cells
+    pub fn render_attacks(&self, by: Color) -> String {
+        let map = self.attacked_map(by);
+        self.render_with(|coord| match map[coord.row as usize][coord.col as usize] {
+            0 => "·".to_string(),
+            n => n.to_string(),
+        })
+    }
+
+    /// Rooks, bishops and queens, walking from `coord` until the first piece
+    fn slider_attackers(&self, coord: &Coord, by: Color) -> Vec<Coord> {
+        let mut attackers = vec![];
+
+        for direction in [
+            Direction::North,
+            Direction::South,
+            Direction::East,
+            Direction::West,
+            Direction::NorthEast,
+            Direction::NorthWest,
+            Direction::SouthEast,
+            Direction::SouthWest,
+        ] {
+            let orthogonal = matches!(
+                direction,
+                Direction::North | Direction::South | Direction::East | Direction::West
+            );
+            for current in direction_iter(coord, &direction) {
+                let cell = match self.get_piece(&current) {
+                    Ok(cell) => cell,
+                    Err(_) => break,
+                };
+                if let Some(piece) = cell {
+                    let slides = match piece.piece {
+                        PieceType::Queen => true,
+                        PieceType::Rook => orthogonal,
+                        PieceType::Bishop => !orthogonal,
+                        _ => false,
+                    };
+
+                    if piece.color == by && slides {
+                        attackers.push(current);
+                    }
+                    break;
+                }
+            }
+        }
+
+        attackers
+    }
+
+    /// Generic path: asks each piece's moves if it could capture in `coord`
+    fn move_attackers(&self, coord: &Coord, by: Color, pieces: &[&Piece]) -> Vec<Coord> {
+        // Put an enemy piece in the target cell so captures (pawns) are valid
+        let mut board = self.fork();
+        board.set_piece(Piece::new(by.opposite(), PieceType::Pawn, vec![], *coord));
+
+        pieces
+            .iter()
+            .filter(|piece| piece.coord != *coord)
+            .filter(|piece| {
+                piece
+                    .moves
+                    .iter()
+                    .any(|m| m.attacks(piece.coord, *coord, &board))
+            })
+            .map(|piece| piece.coord)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::PieceRegistry;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_tables() {
+        // corner knight
+        assert_eq!(knight_attacks(&Coord { row: 0, col: 0 }).len(), 2);
+        assert_eq!(knight_attacks(&Coord { row: 3, col: 3 }).len(), 8);
+
+        assert_eq!(king_attacks(&Coord { row: 7, col: 7 }).len(), 3);
+        assert_eq!(king_attacks(&Coord { row: 4, col: 4 }).len(), 8);
+
+        // e2 pawn attacks d3 and f3
+        let e2 = Coord { row: 6, col: 4 };
+        let attacks = pawn_attacks(&e2, Color::White);
+        assert_eq!(attacks.len(), 2);
+        assert!(attacks.contains(&Coord { row: 5, col: 3 }));
+        assert!(attacks.contains(&Coord { row: 5, col: 5 }));
+        assert!(pawn_attacks(&e2, Color::Black).contains(&Coord { row: 7, col: 3 }));
+    }
+
+    #[test]
+    fn test_initial_position() {
+        let board = Board::default();
+
+        // f3 is attacked by the g1 knight, e2 and g2 pawns
+        let f3 = Coord { row: 5, col: 5 };
+        assert_eq!(board.attackers(&f3, Color::White).len(), 3);
+        assert!(!board.is_square_attacked(&f3, Color::Black));
+
+        // nothing reaches the 4th rank
+        assert!(!board.is_square_attacked(&Coord { row: 4, col: 4 }, Color::White));
+
+        // defended pieces are attacked by their defenders: d1 queen defends e2
+        assert!(board
+            .attackers(&Coord { row: 6, col: 4 }, Color::White)
+            .contains(&Coord { row: 7, col: 3 }));
+    }
+
+    #[test]
+    fn test_sliders() {
+        let board = Board::from_fen("4k3/8/8/8/1b6/8/3P4/R3K3 w - - 0 1").unwrap();
+
+        // the d2 pawn blocks the b4 bishop
+        let e1 = Coord { row: 7, col: 4 };
+        assert!(!board.is_square_attacked(&e1, Color::Black));
+
+        // a1 rook attacks d1 but not f1 (king in the way)
+        assert!(board.is_square_attacked(&Coord { row: 7, col: 3 }, Color::White));
+        assert_eq!(
+            board.attackers(&Coord { row: 7, col: 5 }, Color::White),
+            vec![e1]
+        );
+
+        let board = Board::from_fen("4k3/8/8/8/1b6/8/8/R3K3 w - - 0 1").unwrap();
+        assert!(board.is_square_attacked(&e1, Color::Black));
+    }
+
+    #[test]
+    fn test_attacked_map() {
+        let board = Board::default();
+        let map = board.attacked_map(Color::White);
+
+        assert_eq!(map.len(), 8);
+        // f3: g1 knight, e2 and g2 pawns
+        assert_eq!(map[5][5], 3);
+        assert_eq!(map[4][4], 0);
+        assert_eq!(map[0][0], 0);
+
+        let render = board.render_attacks(Color::White);
+        assert!(render
+            .lines()
+            .nth(5)
+            .unwrap()
+            .starts_with("3 2 2 3 2 2 3 2 2"));
+    }
+
+    #[test]
+    fn test_fallback() {
+        // Same answers through the Move trait on a bigger board
+        let mut board = Board::new(Some(10), Some(10));
+        let registry = PieceRegistry::standard();
+        board.set_piece(registry.create('N', Coord { row: 5, col: 5 }).unwrap());
+        board.set_piece(registry.create('p', Coord { row: 2, col: 2 }).unwrap());
+
+        assert!(board.is_square_attacked(&Coord { row: 3, col: 4 }, Color::White));
+        assert!(!board.is_square_attacked(&Coord { row: 4, col: 4 }, Color::White));
+        // pawn captures diagonally forward, even on an empty cell
+        assert!(board.is_square_attacked(&Coord { row: 3, col: 1 }, Color::Black));
+        assert!(!board.is_square_attacked(&Coord { row: 3, col: 2 }, Color::Black));
+
+        // Custom piece on a standard board
+        let mut board = Board::new(None, None);
+        let mut registry = PieceRegistry::standard();
+        registry
+            .register(
+                "wazir",
+                'w',
+                vec![Arc::new(crate::moves::Line::new(Some(1)))],
+            )
+            .unwrap();
+        board.set_piece(registry.create('W', Coord { row: 4, col: 4 }).unwrap());
+        assert!(board.is_square_attacked(&Coord { row: 3, col: 4 }, Color::White));
+        assert!(!board.is_square_attacked(&Coord { row: 3, col: 3 }, Color::White));
+    }
+}
@@ -0,0 +1,515 @@
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::fmt;
+use std::ops::{BitAnd, BitOr, BitXor, Not, Shl, Shr, Sub};
+
+use super::{Board, Coord};
+use crate::piece::Color;
+
+const SIZE: i32 = 8;
+
+////////////////////////////////////////////////
+// BITBOARD
+////////////////////////////////////////////////
+
+/// Set of cells of an 8x8 board, one bit per cell.
+///
+/// Bit `row * 8 + col` is set if the cell is in the set, so bit 0 is a8 and
+/// bit 63 is h1 (row 0 is the 8th rank, like in `Board`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "python", pyclass)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    pub const EMPTY: Bitboard = Bitboard(0);
+    pub const FULL: Bitboard = Bitboard(u64::MAX);
+
+    /// Cells where (row + col) is even: a8, h1...
+    pub const LIGHT_SQUARES: Bitboard = Bitboard(0xaa55_aa55_aa55_aa55);
+    /// Cells where (row + col) is odd: a1, h8...
+    pub const DARK_SQUARES: Bitboard = Bitboard(0x55aa_55aa_55aa_55aa);
+
+    pub(crate) fn index(coord: &Coord) -> Option<u32> {
+        if coord.row < 0 || coord.col < 0 || coord.row >= SIZE || coord.col >= SIZE {
+            return None;
+        }
+        Some((coord.row * SIZE + coord.col) as u32)
+    }
+
+    pub fn from_coords(coords: &[Coord]) -> Self {
+        let mut bitboard = Self::EMPTY;
+        for coord in coords {
+            bitboard.insert(coord);
+        }
+        bitboard
+    }
+
+    /// All the cells of a board row (row 0 -> 8th rank)
+    pub fn row(row: i32) -> Self {
+        let coords: Vec<Coord> = (0..SIZE).map(|col| Coord { row, col }).collect();
+        Self::from_coords(&coords)
+    }
+
+    /// All the cells of a board column (col 0 -> a file)
+    pub fn col(col: i32) -> Self {
+        let coords: Vec<Coord> = (0..SIZE).map(|row| Coord { row, col }).collect();
+        Self::from_coords(&coords)
+    }
+
+    /// d4, e4, d5, e5
+    pub fn center() -> Self {
+        Self::rect(3, 4, 3, 4)
+    }
+
+    /// c3 to f6
+    pub fn extended_center() -> Self {
+        Self::rect(2, 5, 2, 5)
+    }
+
+    /// a to d files
+    pub fn queenside() -> Self {
+        Self::rect(0, SIZE - 1, 0, 3)
+    }
+
+    /// e to h files
+    pub fn kingside() -> Self {
+        Self::rect(0, SIZE - 1, 4, SIZE - 1)
+    }
+
+    /// Half of the board on the `color` side (ranks 1-4 for white)
+    pub fn half(color: Color) -> Self {
+        match color {
+            Color::White => Self::rect(4, SIZE - 1, 0, SIZE - 1),
+            Color::Black => Self::rect(0, 3, 0, SIZE - 1),
+        }
+    }
+
+    /// Rectangle of cells between the given rows and cols (both inclusive)
+    pub fn rect(from_row: i32, to_row: i32, from_col: i32, to_col: i32) -> Self {
+        let mut bitboard = Self::EMPTY;
+        for row in from_row..=to_row {
+            for col in from_col..=to_col {
+                bitboard.insert(&Coord { row, col });
+            }
+        }
+        bitboard
+    }
+
+    /// Adds a cell to the set, cells outside of the 8x8 board are ignored
+    pub fn insert(&mut self, coord: &Coord) {
+        if let Some(index) = Self::index(coord) {
+            self.0 |= 1 << index;
+        }
+    }
+
+    pub fn remove(&mut self, coord: &Coord) {
+        if let Some(index) = Self::index(coord) {
+            self.0 &= !(1 << index);
+        }
+    }
+
+    pub fn contains(&self, coord: &Coord) -> bool {
+        match Self::index(coord) {
+            Some(index) => self.0 & (1 << index) != 0,
+            None => false,
+        }
+    }
+
+    pub fn len(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Cells in the set, from a8 to h1
+    pub fn iter(&self) -> BitboardIter {
+        BitboardIter { bits: self.0 }
+    }
+}
+
+/// Iterator over the cells of a `Bitboard`, lowest bit first
+pub struct BitboardIter {
+    bits: u64,
+}
+
+impl Iterator for BitboardIter {
+    type Item = Coord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bits == 0 {
+            return None;
+        }
+
+        let index = self.bits.trailing_zeros() as i32;
+        self.bits &= self.bits - 1; // clear the lowest bit
+
+        Some(Coord {
+            row: index / SIZE,
+            col: index % SIZE,
+        })
+    }
+}
+
+impl IntoIterator for Bitboard {
+    type Item = Coord;
+    type IntoIter = BitboardIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<Coord> for Bitboard {
+    fn from_iter<I: IntoIterator<Item = Coord>>(iter: I) -> Self {
+        let mut bitboard = Self::EMPTY;
+        for coord in iter {
+            bitboard.insert(&coord);
+        }
+        bitboard
+    }
+}
+
+impl BitAnd for Bitboard {
+    type Output = Bitboard;
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 & rhs.0)
+    }
+}
+
+impl BitOr for Bitboard {
+    type Output = Bitboard;
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 | rhs.0)
+    }
+}
+
+impl BitXor for Bitboard {
+    type Output = Bitboard;
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 ^ rhs.0)
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Bitboard;
+    fn not(self) -> Self::Output {
+        Bitboard(!self.0)
+    }
+}
+
+/// Set difference
+impl Sub for Bitboard {
+    type Output = Bitboard;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 & !rhs.0)
+    }
+}
+
+/// Raw bit shift: `<< 1` moves every cell one column east (wrapping to the
+/// next row), `<< 8` one row south. Bits shifted out are dropped.
+impl Shl<u32> for Bitboard {
+    type Output = Bitboard;
+    fn shl(self, rhs: u32) -> Self::Output {
+        Bitboard(self.0.checked_shl(rhs).unwrap_or(0))
+    }
+}
+
+impl Shr<u32> for Bitboard {
+    type Output = Bitboard;
+    fn shr(self, rhs: u32) -> Self::Output {
+        Bitboard(self.0.checked_shr(rhs).unwrap_or(0))
+    }
+}
+
+/// Same layout as the `Board` display: 8th rank on top
+impl fmt::Display for Bitboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = String::new();
+
+        for row in 0..SIZE {
+            s.push_str(&format!("{} ", SIZE - row));
+            for col in 0..SIZE {
+                match self.contains(&Coord { row, col }) {
+                    true => s.push_str("1 "),
+                    false => s.push_str("· "),
+                }
+            }
+            s.push('\n');
+        }
+
+        s.push_str("  ");
+        for col in 0..SIZE {
+            s.push_str(&format!("{} ", (b'a' + col as u8) as char));
+        }
+        s.push('\n');
+
+        write!(f, "{}", s)
+    }
+}
+
+#[cfg(feature = "python")]
+mod python {
+    // pyo3 0.18 expands the number protocol methods (__and__, __or__...) into nested impls
+    #![allow(non_local_definitions)]
+
+    use super::*;
+    use pyo3::pyclass::CompareOp;
+
+    #[pymethods]
+    impl Bitboard {
+        #[new]
+        #[pyo3(signature = (bits = 0))]
+        fn py_new(bits: u64) -> Self {
+            Bitboard(bits)
+        }
+
+        #[staticmethod]
+        #[pyo3(name = "from_coords")]
+        fn py_from_coords(coords: Vec<Coord>) -> Self {
+            Self::from_coords(&coords)
+        }
+
+        #[staticmethod]
+        #[pyo3(name = "row")]
+        fn py_row(row: i32) -> Self {
+            Self::row(row)
+        }
+
+        #[staticmethod]
+        #[pyo3(name = "col")]
+        fn py_col(col: i32) -> Self {
+            Self::col(col)
+        }
+
+        #[staticmethod]
+        #[pyo3(name = "center")]
+        fn py_center() -> Self {
+            Self::center()
+        }
+
+        #[staticmethod]
+        #[pyo3(name = "extended_center")]
+        fn py_extended_center() -> Self {
+            Self::extended_center()
+        }
+
+        #[staticmethod]
+        #[pyo3(name = "queenside")]
+        fn py_queenside() -> Self {
+            Self::queenside()
+        }
+
+        #[staticmethod]
+        #[pyo3(name = "kingside")]
+        fn py_kingside() -> Self {
+            Self::kingside()
+        }
+
+        #[staticmethod]
+        #[pyo3(name = "light_squares")]
+        fn py_light_squares() -> Self {
+            Self::LIGHT_SQUARES
+        }
+
+        #[staticmethod]
+        #[pyo3(name = "dark_squares")]
+        fn py_dark_squares() -> Self {
+            Self::DARK_SQUARES
+        }
+
+        #[getter]
+        fn bits(&self) -> u64 {
+            self.0
+        }
+
+        fn __and__(&self, other: &Self) -> Self {
+            *self & *other
+        }
+
+        fn __or__(&self, other: &Self) -> Self {
+            *self | *other
+        }
+
+        fn __xor__(&self, other: &Self) -> Self {
+            *self ^ *other
+        }
+
+        fn __sub__(&self, other: &Self) -> Self {
+            *self - *other
+        }
+
+        fn __invert__(&self) -> Self {
+            !*self
+        }
+
+        fn __lshift__(&self, n: u32) -> Self {
+            *self << n
+        }
+
+        fn __rshift__(&self, n: u32) -> Self {
+            *self >> n
+        }
+
+        fn __len__(&self) -> usize {
+            self.len() as usize
+        }
+
+        fn __bool__(&self) -> bool {
+            !self.is_empty()
+        }
+
+        fn __contains__(&self, coord: &Coord) -> bool {
+            self.contains(coord)
+        }
+
+        fn __iter__(&self, py: Python<'_>) -> PyResult<PyObject> {
+            let coords: Vec<Coord> = self.iter().collect();
+            Ok(coords.into_py(py).as_ref(py).iter()?.into())
+        }
+
+        fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> PyObject {
+            match op {
+                CompareOp::Eq => (self == other).into_py(py),
+                CompareOp::Ne => (self != other).into_py(py),
+                _ => py.NotImplemented(),
+            }
+        }
+
+        fn __hash__(&self) -> u64 {
+            self.0
+        }
+
+        fn __str__(&self) -> String {
+            self.to_string()
+        }
+
+        fn __repr__(&self) -> String {
+            format!("Bitboard(0x{:016x})", self.0)
+        }
+    }
+}
+
+////////////////////////////////////////////////
+// ZONE QUERIES
+////////////////////////////////////////////////
+
+impl Board {
+    /// Cells occupied by `color`. Only the 8x8 top left corner of bigger boards is included.
+    pub fn occupied_by(&self, color: &Color) -> Bitboard {
+        let coords: Vec<Coord> = self
+            .get_all_pieces(color)
+            .iter()
+            .map(|piece| piece.coord)
+            .collect();
+        Bitboard::from_coords(&coords)
+    }
+
+    /// Number of `color` pieces inside `region`
+    pub fn count_in(&self, region: Bitboard, color: &Color) -> u32 {
+        (self.occupied_by(color) & region).len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regions() {
+        assert_eq!(Bitboard::center().len(), 4);
+        assert!(Bitboard::center().contains(&Coord { row: 4, col: 4 })); // e4
+        assert_eq!(Bitboard::extended_center().len(), 16);
+        assert_eq!((Bitboard::center() - Bitboard::extended_center()).len(), 0);
+
+        assert_eq!(Bitboard::queenside() | Bitboard::kingside(), Bitboard::FULL);
+        assert_eq!(
+            Bitboard::queenside() & Bitboard::kingside(),
+            Bitboard::EMPTY
+        );
+        assert_eq!(!Bitboard::queenside(), Bitboard::kingside());
+        assert_eq!(
+            Bitboard::half(Color::White) ^ Bitboard::half(Color::Black),
+            Bitboard::FULL
+        );
+
+        assert_eq!(Bitboard::row(0).len(), 8);
+        assert_eq!((Bitboard::row(0) & Bitboard::col(0)).len(), 1);
+    }
+
+    #[test]
+    fn test_color_complexes() {
+        // a1 is dark, h1 is light
+        assert!(Bitboard::DARK_SQUARES.contains(&Coord { row: 7, col: 0 }));
+        assert!(Bitboard::LIGHT_SQUARES.contains(&Coord { row: 7, col: 7 }));
+        assert_eq!(
+            Bitboard::LIGHT_SQUARES | Bitboard::DARK_SQUARES,
+            Bitboard::FULL
+        );
+        assert_eq!(Bitboard::LIGHT_SQUARES.len(), 32);
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut bitboard = Bitboard::EMPTY;
+        let coord = Coord { row: 2, col: 5 };
+
+        bitboard.insert(&coord);
+        bitboard.insert(&Coord { row: 8, col: 0 }); // out of bounds
+        assert!(bitboard.contains(&coord));
+        assert_eq!(bitboard.len(), 1);
+
+        bitboard.remove(&coord);
+        assert!(bitboard.is_empty());
+    }
+
+    #[test]
+    fn test_shift_and_iter() {
+        let a8 = Bitboard::from_coords(&[Coord { row: 0, col: 0 }]);
+
+        assert!((a8 << 1).contains(&Coord { row: 0, col: 1 }));
+        assert!((a8 << 8).contains(&Coord { row: 1, col: 0 }));
+        assert_eq!(a8 >> 1, Bitboard::EMPTY);
+        assert_eq!(a8 << 64, Bitboard::EMPTY);
+
+        let coords: Vec<Coord> = Bitboard::center().iter().collect();
+        assert_eq!(
+            coords,
+            vec![
+                Coord { row: 3, col: 3 },
+                Coord { row: 3, col: 4 },
+                Coord { row: 4, col: 3 },
+                Coord { row: 4, col: 4 },
+            ]
+        );
+        assert_eq!(Bitboard::FULL.into_iter().count(), 64);
+    }
+
+    #[test]
+    fn test_display() {
+        let s = Bitboard::row(0).to_string();
+        let mut lines = s.lines();
+
+        assert_eq!(lines.next(), Some("8 1 1 1 1 1 1 1 1 "));
+        assert_eq!(lines.next(), Some("7 · · · · · · · · "));
+        assert_eq!(lines.last(), Some("  a b c d e f g h "));
+    }
+
+    #[test]
+    fn test_zone_queries() {
+        let board = Board::default();
+
+        assert_eq!(
+            board.occupied_by(&Color::White),
+            Bitboard::row(6) | Bitboard::row(7)
+        );
+        assert_eq!(board.count_in(Bitboard::center(), &Color::White), 0);
+        assert_eq!(board.count_in(Bitboard::kingside(), &Color::Black), 8);
+
+        let board = Board::from_fen("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(board.count_in(Bitboard::center(), &Color::White), 1);
+        assert_eq!(board.count_in(Bitboard::center(), &Color::Black), 1);
+        assert_eq!(board.count_in(Bitboard::DARK_SQUARES, &Color::White), 1); // e1
+    }
+}
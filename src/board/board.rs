@@ -1,13 +1,26 @@
 use crate::PieceType;
 use crate::{moves::Direction, notation::FenError};
 
-use super::{BoardInfo, Coord, HasCoordinates};
+#[cfg(feature = "python")]
+use super::{Bitboard, Handicap, MoveError, PositionDescriptor, ANNOTATION_COLOR};
+use super::effects::MoveEffects;
+use super::move_cache::MoveCache;
+use super::observers::Observers;
+use super::pins::CheckCache;
+use super::Annotations;
+use super::{BoardInfo, Coord, HasCoordinates, MoveRecord};
 use crate::errors::OutOfBoundsError;
 use crate::notation::fen;
-use crate::notation::fen::parse as parse_fen;
+use crate::notation::fen::parse_sized as parse_fen;
 use crate::piece::{Color, Piece};
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::pyclass::CompareOp;
 use std::cmp;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
 const ROWS: u32 = 8;
 const COLS: u32 = 8;
@@ -16,11 +29,35 @@ const COLS: u32 = 8;
 // BOARD
 ////////////////////////////////////////////////
 
-#[pyclass]
+/// Cells and info of a position, restored by `Board::pop`
+#[derive(Clone)]
+pub(super) struct Snapshot {
+    board: Vec<Arc<Vec<Option<Piece>>>>,
+    info: BoardInfo,
+}
+
+/// Boards are `Send + Sync`, so they can be shared between threads. From
+/// Python, concurrent access is checked by pyo3 (mutating a board while another
+/// thread borrows it raises a `RuntimeError`); use `copy()` to give each thread
+/// its own board.
+#[cfg_attr(feature = "python", pyclass)]
 #[derive(Clone)]
 pub struct Board {
-    board: Vec<Vec<Option<Piece>>>,
+    /// Rows are shared between clones and copied on write (see `fork`)
+    board: Vec<Arc<Vec<Option<Piece>>>>,
     pub info: BoardInfo,
+    /// Arrows, highlights and labels drawn by `to_svg` and `Display`
+    pub annotations: Annotations,
+    /// Moves played with `push`, with the position before each of them
+    history: Vec<(Snapshot, MoveRecord)>,
+    /// Not shared with clones, see `add_observer`
+    pub(super) observers: Observers,
+    /// Variant rules applied by every move, see `add_move_effect`
+    pub(super) effects: MoveEffects,
+    /// Not shared with clones, see `cached_legal_moves`
+    pub(super) move_cache: MoveCache,
+    /// See `check_info`
+    pub(super) check_cache: CheckCache,
 
     n_rows: u32,
     n_cols: u32,
@@ -32,11 +69,11 @@ impl Board {
         let n_rows = n_rows.unwrap_or(ROWS);
         let n_cols = n_cols.unwrap_or(COLS);
 
-        let mut board: Vec<Vec<Option<Piece>>> = Vec::new();
+        let mut board: Vec<Arc<Vec<Option<Piece>>>> = Vec::new();
         // Fill the matrix with cells
         for _ in 0..n_rows {
             let row = (0..n_cols).map(|_| None).collect();
-            board.push(row);
+            board.push(Arc::new(row));
         }
 
         Self {
@@ -44,6 +81,12 @@ impl Board {
             n_rows,
             n_cols,
             info: BoardInfo::default(),
+            annotations: Annotations::default(),
+            history: vec![],
+            observers: Observers::default(),
+            effects: MoveEffects::default(),
+            move_cache: MoveCache::default(),
+            check_cache: CheckCache::default(),
         }
     }
 
@@ -53,24 +96,109 @@ impl Board {
     }
 
     pub fn set_piece(&mut self, piece: Piece) {
-        let Coord { row, col } = piece.coord;
-        self.board[row as usize][col as usize] = Some(piece);
+        let coord = piece.coord;
+        *self.cell_mut(&coord) = Some(piece);
     }
 
     pub fn remove_piece(&mut self, coord: &Coord) {
-        self.board[coord.row as usize][coord.col as usize] = None;
+        *self.cell_mut(coord) = None;
+    }
+
+    /// Mutable cell, copying its row first if it is shared with a fork
+    fn cell_mut(&mut self, coord: &Coord) -> &mut Option<Piece> {
+        self.invalidate_check_info();
+        &mut Arc::make_mut(&mut self.board[coord.row as usize])[coord.col as usize]
+    }
+
+    /// Cheap copy of the board for tree search: the rows are shared with this
+    /// board and only copied when one of the boards modifies them.
+    ///
+    /// The move history is not copied, so moves played before the fork can't
+    /// be popped from it.
+    pub fn fork(&self) -> Self {
+        Self {
+            board: self.board.clone(),
+            info: self.info.clone(),
+            annotations: Annotations::default(),
+            history: vec![],
+            observers: Observers::default(),
+            effects: self.effects.clone(),
+            move_cache: MoveCache::default(),
+            check_cache: self.check_cache.clone(),
+            n_rows: self.n_rows,
+            n_cols: self.n_cols,
+        }
+    }
+
+    pub(super) fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            board: self.board.clone(),
+            info: self.info.clone(),
+        }
+    }
+
+    pub(super) fn restore(&mut self, snapshot: Snapshot) {
+        self.board = snapshot.board;
+        self.info = snapshot.info;
+        self.invalidate_check_info();
+    }
+
+    pub(super) fn record_move(&mut self, snapshot: Snapshot, record: MoveRecord) {
+        self.history.push((snapshot, record));
+        self.annotations.clear();
+
+        #[cfg(feature = "debug-checks")]
+        self.assert_consistent();
+    }
+
+    /// Takes back the last move played with `push`, returning it
+    pub fn pop(&mut self) -> Option<MoveRecord> {
+        let (snapshot, record) = self.history.pop()?;
+        self.restore(snapshot);
+        self.annotations.clear();
+
+        #[cfg(feature = "debug-checks")]
+        self.assert_consistent();
+
+        Some(record)
+    }
+
+    /// Board before the first move of `move_history`
+    pub fn start_position(&self) -> Board {
+        let mut board = self.fork();
+        if let Some((snapshot, _)) = self.history.first() {
+            board.restore(snapshot.clone());
+        }
+        board
+    }
+
+    /// Each move of `move_history` with the board it was played on
+    pub(super) fn history_boards(&self) -> Vec<(Board, &MoveRecord)> {
+        self.history
+            .iter()
+            .map(|(snapshot, record)| {
+                let mut board = self.fork();
+                board.restore(snapshot.clone());
+                (board, record)
+            })
+            .collect()
+    }
+
+    /// Moves played with `push`, oldest first
+    pub fn move_history(&self) -> Vec<&MoveRecord> {
+        self.history.iter().map(|(_, record)| record).collect()
     }
 
     pub fn move_to_coord(&mut self, from: &Coord, to: &Coord) -> Option<Piece> {
-        let mut piece = self.board[from.row as usize][from.col as usize].take();
+        let mut piece = self.cell_mut(from).take();
 
         if piece.is_some() {
             // update the piece's coordinates
             piece.as_mut().unwrap().coord = *to;
         }
 
-        let old_piece = self.board[to.row as usize][to.col as usize].take();
-        self.board[to.row as usize][to.col as usize] = piece;
+        let old_piece = self.cell_mut(to).take();
+        *self.cell_mut(to) = piece;
         return old_piece;
     }
 
@@ -84,7 +212,7 @@ impl Board {
             return Err(OutOfBoundsError);
         }
 
-        Ok(&mut self.board[row as usize][col as usize])
+        Ok(self.cell_mut(&Coord { row, col }))
     }
 
     pub fn get_rows(&self) -> u32 {
@@ -104,18 +232,26 @@ impl Board {
         }
     }
 
-    pub fn is_promotion_row(&self, row: i32, color: Color) -> bool {
+    /// Rank of a row counted from 0 and from `color`'s side, e.g. row 6 is
+    /// rank 1 for White and rank 6 for Black (see `Coord`)
+    pub fn relative_rank(&self, row: i32, color: Color) -> i32 {
         match color {
-            Color::White => row == 0,
-            Color::Black => row == self.n_rows as i32 - 1,
+            Color::White => self.n_rows as i32 - 1 - row,
+            Color::Black => row,
         }
     }
 
+    /// Last rank for `color`
+    pub fn is_promotion_row(&self, row: i32, color: Color) -> bool {
+        self.relative_rank(row, color) == self.n_rows as i32 - 1
+    }
+
+    /// True if `color` pawns can move two cells from this row, see
+    /// `BoardInfo::double_step_ranks`
     pub fn is_pawn_row(&self, row: i32, color: Color) -> bool {
-        match color {
-            Color::White => row == self.n_rows as i32 - 2,
-            Color::Black => row == 1,
-        }
+        self.info
+            .double_step_ranks
+            .contains(&self.relative_rank(row, color))
     }
 
     pub fn get_piece(&self, coords: &Coord) -> Result<Option<&Piece>, OutOfBoundsError> {
@@ -128,6 +264,75 @@ impl Board {
         Ok(self.board[row as usize][col as usize].as_ref())
     }
 
+    /// Piece in a cell written in algebraic notation, e.g. "e4". None if the
+    /// cell is empty or not valid.
+    pub fn piece_at(&self, cell: &str) -> Option<&Piece> {
+        let coord = self.notation().cell_from_str(cell).ok()?;
+        self.get_piece(&coord).ok().flatten()
+    }
+
+    /// Every cell of the board with its piece, row by row
+    pub(super) fn cells(&self) -> impl Iterator<Item = (Coord, Option<&Piece>)> + '_ {
+        self.board.iter().enumerate().flat_map(|(row, cells)| {
+            cells.iter().enumerate().map(move |(col, cell)| {
+                let coord = Coord {
+                    row: row as i32,
+                    col: col as i32,
+                };
+                (coord, cell.as_ref())
+            })
+        })
+    }
+
+    pub fn occupied_squares(&self) -> impl Iterator<Item = Coord> + '_ {
+        self.cells()
+            .filter(|(_, piece)| piece.is_some())
+            .map(|(coord, _)| coord)
+    }
+
+    pub fn empty_squares(&self) -> impl Iterator<Item = Coord> + '_ {
+        self.cells()
+            .filter(|(_, piece)| piece.is_none())
+            .map(|(coord, _)| coord)
+    }
+
+    /// Pieces of a color and type, e.g. the white rooks
+    pub fn pieces_of(&self, color: Color, piece: PieceType) -> impl Iterator<Item = &Piece> + '_ {
+        self.cells()
+            .filter_map(|(_, cell)| cell)
+            .filter(move |p| p.color == color && p.piece == piece)
+    }
+
+    /// `PieceType::code` of the piece in each cell (0 if empty), indexed
+    /// `[row][col]`
+    pub fn to_array(&self) -> Vec<Vec<u8>> {
+        self.board
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.as_ref().map_or(0, |piece| piece.piece.code()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Color of the piece in each cell, 1 for White, -1 for Black and 0 if
+    /// empty, indexed `[row][col]`
+    pub fn color_array(&self) -> Vec<Vec<i8>> {
+        self.board
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match cell.as_ref().map(|piece| piece.color) {
+                        Some(Color::White) => 1,
+                        Some(Color::Black) => -1,
+                        None => 0,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
     pub fn get_all_pieces(&self, color: &Color) -> Vec<&Piece> {
         let mut pieces = Vec::new();
         for row in self.board.iter() {
@@ -153,12 +358,22 @@ impl Board {
         self.move_to_coord(to, from);
 
         if to_piece.is_some() {
-            self.board[to.row as usize][to.col as usize] = to_piece;
+            *self.cell_mut(to) = to_piece;
         }
 
         res
     }
 
+    /// Cells of the royal pieces of `color`: none (e.g. antichess), one king,
+    /// or several royals
+    pub fn royals(&self, color: &Color) -> Vec<Coord> {
+        self.get_all_pieces(color)
+            .iter()
+            .filter(|piece| piece.royal)
+            .map(|piece| piece.coord)
+            .collect()
+    }
+
     pub fn get_king(&self, color: &Color) -> &Piece {
         for row in self.board.iter() {
             for cell in row.iter() {
@@ -171,21 +386,98 @@ impl Board {
         }
         unreachable!("There should be a king on the board")
     }
+
+    /// Castling rights in a fixed order, so two boards with the same rights
+    /// compare equal regardless of how they were inserted
+    fn castling_key(&self) -> Vec<(Color, Coord, Coord)> {
+        let mut key = vec![];
+        for color in [Color::White, Color::Black] {
+            let mut rights: Vec<(Color, Coord, Coord)> = match self.info.castling.get(&color) {
+                Some(rights) => rights.iter().map(|r| (color, r.new_king, r.rook)).collect(),
+                None => continue,
+            };
+            rights.sort_by_key(|(_, king, rook)| (king.row, king.col, rook.row, rook.col));
+            key.append(&mut rights);
+        }
+        key
+    }
+
+    /// Check limit and checks given by each color
+    fn checks_key(&self) -> (Option<u32>, u32, u32) {
+        (
+            self.info.check_limit,
+            self.info.checks_given(Color::White),
+            self.info.checks_given(Color::Black),
+        )
+    }
+
+    /// Hash of the position: pieces, side to move, castling rights, en passant
+    /// and three-check counters.
+    ///
+    /// Move counters are not part of the position.
+    pub fn position_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// Two boards are equal if they hold the same position: same pieces in the
+/// same cells, side to move, castling rights, en passant cell and, in
+/// three-check, the checks given.
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        if self.n_rows != other.n_rows || self.n_cols != other.n_cols {
+            return false;
+        }
+
+        let same_pieces = self
+            .board
+            .iter()
+            .flat_map(|row| row.iter())
+            .zip(other.board.iter().flat_map(|row| row.iter()))
+            .all(|(a, b)| match (a, b) {
+                (Some(a), Some(b)) => a.color == b.color && a.piece == b.piece,
+                (None, None) => true,
+                _ => false,
+            });
+
+        same_pieces
+            && self.info.turn == other.info.turn
+            && self.info.en_passant == other.info.en_passant
+            && self.castling_key() == other.castling_key()
+            && self.checks_key() == other.checks_key()
+    }
+}
+
+impl Eq for Board {}
+
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.n_rows.hash(state);
+        self.n_cols.hash(state);
+
+        for cell in self.board.iter().flat_map(|row| row.iter()) {
+            cell.as_ref().map(|p| (p.color, p.piece)).hash(state);
+        }
+
+        self.info.turn.hash(state);
+        self.info.en_passant.hash(state);
+        self.castling_key().hash(state);
+        self.checks_key().hash(state);
+    }
 }
 
-#[pymethods]
 impl Board {
-    #[staticmethod]
     pub fn default() -> Self {
         Self::from_fen(fen::INITIAL_BOARD).unwrap()
     }
 
-    #[staticmethod]
-    #[args(fen = "fen::INITIAL_BOARD")]
+    /// The board size is taken from the FEN, see `fen::parse_sized`
     pub fn from_fen(fen: &str) -> Result<Self, FenError> {
-        let (pieces, info) = parse_fen(fen)?;
+        let (pieces, info, rows, cols) = parse_fen(fen)?;
 
-        let mut board = Self::new(None, None);
+        let mut board = Self::new(Some(rows), Some(cols));
         for piece in pieces {
             board.set_piece(piece);
         }
@@ -194,12 +486,25 @@ impl Board {
         Ok(board)
     }
 
-    pub fn can_move(&self, from: &Coord, to: &Coord) -> bool {
+    /// See `fen::write`
+    pub fn to_fen(&self) -> String {
+        fen::write(self)
+    }
+
+    /// Checks if the piece in `from` can move to `to`.
+    ///
+    /// Only the side to move (`info.turn`) can move, unless `ignore_turn` is set
+    /// (analysis, e.g. "where could this piece go?").
+    pub fn can_move(&self, from: &Coord, to: &Coord, ignore_turn: bool) -> bool {
         let piece = match self.get_piece(from) {
             Ok(Some(piece)) => piece,
             _ => return false,
         };
 
+        if !ignore_turn && piece.color != self.info.turn {
+            return false;
+        }
+
         for move_ in piece.moves.iter() {
             if move_.is_move_valid(*from, *to, self) {
                 return true;
@@ -209,33 +514,464 @@ impl Board {
         return false;
     }
 
-    pub fn move_piece(&mut self, from: &Coord, to: &Coord, promote: Option<Piece>) {
+    /// Returns the cells the piece in `from` can move to.
+    ///
+    /// Empty if the cell is empty or, unless `ignore_turn` is set, if it is not
+    /// the piece's turn. The king is not checked to be left safe.
+    ///
+    /// Cells are sorted in `Coord` order, so the order is the same on every
+    /// run and platform.
+    pub fn get_moves(&self, from: &Coord, ignore_turn: bool) -> Vec<Coord> {
         let piece = match self.get_piece(from) {
             Ok(Some(piece)) => piece,
-            _ => return,
+            _ => return vec![],
         };
-        // TODO
+
+        if !ignore_turn && piece.color != self.info.turn {
+            return vec![];
+        }
+
+        piece.get_moves(self).into_iter().collect()
+    }
+
+    /// Plays the move if it is legal, see `push` for the details
+    pub fn move_piece(&mut self, from: &Coord, to: &Coord, promote: Option<Piece>) {
+        let _ = self.push(from, to, promote.map(|piece| piece.piece));
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Board {
+    #[staticmethod]
+    #[pyo3(name = "default")]
+    fn py_default() -> Self {
+        Self::default()
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_fen")]
+    #[args(fen = "fen::INITIAL_BOARD")]
+    fn py_from_fen(fen: &str) -> Result<Self, FenError> {
+        Self::from_fen(fen)
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "with_handicap")]
+    fn py_with_handicap(color: Color, handicap: Handicap) -> Self {
+        Self::with_handicap(color, handicap)
+    }
+
+    #[pyo3(name = "occupied_by")]
+    fn py_occupied_by(&self, color: Color) -> Bitboard {
+        self.occupied_by(&color)
+    }
+
+    #[pyo3(name = "checks_given")]
+    fn py_checks_given(&self, color: Color) -> u32 {
+        self.info.checks_given(color)
+    }
+
+    #[pyo3(name = "remaining_checks")]
+    fn py_remaining_checks(&self, color: Color) -> Option<u32> {
+        self.info.remaining_checks(color)
+    }
+
+    #[pyo3(name = "check_limit_winner")]
+    fn py_check_limit_winner(&self) -> Option<Color> {
+        self.info.check_limit_winner()
+    }
+
+    /// `legal_moves`, cached (see `cached_legal_moves`)
+    #[pyo3(name = "legal_moves")]
+    fn py_legal_moves(&self, from: &Coord) -> Vec<Coord> {
+        self.cached_legal_moves(from)
+    }
+
+    /// Hits, misses and entries of the legal move cache
+    #[pyo3(name = "cache_stats")]
+    fn py_cache_stats(&self) -> (u64, u64, usize) {
+        let stats = self.cache_stats();
+        (stats.hits, stats.misses, stats.len)
+    }
+
+    #[pyo3(name = "clear_cache")]
+    fn py_clear_cache(&self) {
+        self.clear_cache()
+    }
+
+    #[pyo3(name = "mobility_map")]
+    fn py_mobility_map(&self) -> Vec<Vec<u32>> {
+        self.mobility_map()
+    }
+
+    #[pyo3(name = "check_consistency")]
+    fn py_check_consistency(&self) -> Vec<String> {
+        self.check_consistency()
+    }
+
+    #[pyo3(name = "piece_at")]
+    fn py_piece_at(&self, cell: &str) -> Option<Piece> {
+        self.piece_at(cell).cloned()
+    }
+
+    #[pyo3(name = "occupied_squares")]
+    fn py_occupied_squares(&self) -> Vec<Coord> {
+        self.occupied_squares().collect()
+    }
+
+    #[pyo3(name = "empty_squares")]
+    fn py_empty_squares(&self) -> Vec<Coord> {
+        self.empty_squares().collect()
+    }
+
+    /// `piece` is a FEN letter, e.g. "r" for rooks
+    #[pyo3(name = "pieces_of")]
+    fn py_pieces_of(&self, color: Color, piece: char) -> Vec<Piece> {
+        self.pieces_of(color, PieceType::from_char(piece))
+            .cloned()
+            .collect()
+    }
+
+    /// Motifs as `(name, cells)`, see `Motif::cells`
+    #[pyo3(name = "motifs")]
+    fn py_motifs(&self, color: Color) -> Vec<(String, Vec<Coord>)> {
+        self.motifs(color)
+            .iter()
+            .map(|motif| (motif.name().to_string(), motif.cells()))
+            .collect()
+    }
+
+    #[pyo3(name = "motifs_after")]
+    fn py_motifs_after(&self, uci: &str) -> Result<Vec<(String, Vec<Coord>)>, MoveError> {
+        let (from, to, promotion) = self.parse_uci(uci)?;
+        Ok(self
+            .motifs_after(&from, &to, promotion)?
+            .iter()
+            .map(|motif| (motif.name().to_string(), motif.cells()))
+            .collect())
+    }
+
+    /// Ranks where pawns can move two cells, counted from 0 from each side
+    #[pyo3(name = "set_double_step_ranks")]
+    fn py_set_double_step_ranks(&mut self, ranks: Vec<i32>) {
+        self.info.double_step_ranks = ranks;
+        self.clear_cache();
+    }
+
+    #[pyo3(name = "to_array")]
+    fn py_to_array(&self) -> Vec<Vec<u8>> {
+        self.to_array()
+    }
+
+    #[pyo3(name = "color_array")]
+    fn py_color_array(&self) -> Vec<Vec<i8>> {
+        self.color_array()
+    }
+
+    /// Names and codes of `to_array`
+    #[staticmethod]
+    #[pyo3(name = "piece_codes")]
+    fn py_piece_codes() -> Vec<(String, u8)> {
+        let mut codes = vec![("empty".to_string(), 0)];
+        codes.extend(
+            [
+                ("pawn", PieceType::Pawn),
+                ("knight", PieceType::Knight),
+                ("bishop", PieceType::Bishop),
+                ("rook", PieceType::Rook),
+                ("queen", PieceType::Queen),
+                ("king", PieceType::King),
+                ("custom", PieceType::Custom('?')),
+            ]
+            .iter()
+            .map(|(name, piece)| (name.to_string(), piece.code())),
+        );
+        codes
+    }
+
+    #[pyo3(name = "only_move")]
+    fn py_only_move(&self) -> Option<String> {
+        let (from, to, promotion) = self.only_move()?;
+        self.move_to_uci(&from, &to, promotion).ok()
+    }
+
+    /// `forced_line` in UCI notation
+    #[pyo3(name = "forced_line")]
+    fn py_forced_line(&self, max_plies: usize) -> Vec<String> {
+        let mut board = self.fork();
+        self.forced_line(max_plies)
+            .iter()
+            .filter_map(|(from, to, promotion)| board.push(from, to, *promotion).ok())
+            .map(|record| record.uci)
+            .collect()
+    }
+
+    #[pyo3(name = "is_checkmate")]
+    fn py_is_checkmate(&self) -> bool {
+        self.is_checkmate()
+    }
+
+    #[pyo3(name = "mate_in_one")]
+    fn py_mate_in_one(&self) -> Option<String> {
+        let (from, to, promotion) = self.mate_in_one()?;
+        self.move_to_uci(&from, &to, promotion).ok()
+    }
+
+    /// First move of `forced_mate` in UCI notation
+    #[pyo3(name = "forced_mate")]
+    fn py_forced_mate(&self, depth: u32) -> Option<String> {
+        let (from, to, promotion) = self.forced_mate(depth)?;
+        self.move_to_uci(&from, &to, promotion).ok()
+    }
+
+    #[pyo3(name = "insufficient_material_for")]
+    fn py_insufficient_material_for(&self, color: Color) -> bool {
+        self.insufficient_material_for(color)
+    }
+
+    #[pyo3(name = "is_insufficient_material")]
+    fn py_is_insufficient_material(&self) -> bool {
+        self.is_insufficient_material()
+    }
+
+    #[pyo3(name = "timeout_winner")]
+    fn py_timeout_winner(&self, flagged: Color) -> Option<Color> {
+        self.timeout_winner(flagged)
+    }
+
+    /// `checking_moves` in UCI notation
+    #[pyo3(name = "checking_moves")]
+    fn py_checking_moves(&self, color: Color) -> Vec<String> {
+        self.checking_moves(color)
+            .iter()
+            .filter_map(|(from, to, promotion)| self.move_to_uci(from, to, *promotion).ok())
+            .collect()
+    }
+
+    #[pyo3(name = "descriptor")]
+    fn py_descriptor(&self) -> PositionDescriptor {
+        self.descriptor()
+    }
+
+    /// `sorted_legal_moves` in UCI notation
+    #[pyo3(name = "sorted_legal_moves")]
+    fn py_sorted_legal_moves(&self) -> Vec<String> {
+        self.sorted_legal_moves()
+            .iter()
+            .filter_map(|(from, to, promotion)| self.move_to_uci(from, to, *promotion).ok())
+            .collect()
+    }
+
+    #[pyo3(name = "legal_move_index")]
+    fn py_legal_move_index(&self, uci: &str) -> Result<Option<usize>, MoveError> {
+        Ok(self.legal_move_index(&self.parse_uci(uci)?))
+    }
+
+    #[pyo3(name = "encode_game")]
+    fn py_encode_game(&self, py: Python<'_>) -> Result<PyObject, MoveError> {
+        Ok(pyo3::types::PyBytes::new(py, &self.encode_game()?).into())
+    }
+
+    #[pyo3(name = "push_encoded")]
+    fn py_push_encoded(&mut self, data: &[u8]) -> Result<Vec<MoveRecord>, MoveError> {
+        self.push_encoded(data)
+    }
+
+    #[pyo3(name = "attacked_map")]
+    fn py_attacked_map(&self, color: Color) -> Vec<Vec<u32>> {
+        self.attacked_map(color)
+    }
+
+    #[pyo3(name = "render_attacks")]
+    fn py_render_attacks(&self, color: Color) -> String {
+        self.render_attacks(color)
+    }
+
+    #[pyo3(name = "checkers")]
+    fn py_checkers(&self) -> Bitboard {
+        self.checkers()
+    }
+
+    #[pyo3(name = "blockers_for_king")]
+    fn py_blockers_for_king(&self, color: Color) -> Bitboard {
+        self.blockers_for_king(color)
+    }
+
+    #[pyo3(name = "can_move", signature = (from, to, ignore_turn = false))]
+    fn py_can_move(&self, from: &Coord, to: &Coord, ignore_turn: bool) -> bool {
+        self.can_move(from, to, ignore_turn)
+    }
+
+    #[pyo3(name = "get_moves", signature = (from, ignore_turn = false))]
+    fn py_get_moves(&self, from: &Coord, ignore_turn: bool) -> Vec<Coord> {
+        self.get_moves(from, ignore_turn)
+    }
+
+    #[pyo3(name = "push_uci")]
+    fn py_push_uci(&mut self, uci: &str) -> Result<MoveRecord, MoveError> {
+        self.push_uci(uci)
+    }
+
+    #[pyo3(name = "push_san")]
+    fn py_push_san(&mut self, san: &str) -> Result<MoveRecord, MoveError> {
+        self.push_san(san)
+    }
+
+    #[pyo3(name = "pop")]
+    fn py_pop(&mut self) -> Option<MoveRecord> {
+        self.pop()
+    }
+
+    #[pyo3(name = "make_null_move")]
+    fn py_make_null_move(&mut self) -> MoveRecord {
+        self.make_null_move()
+    }
+
+    #[pyo3(name = "unmake_null_move")]
+    fn py_unmake_null_move(&mut self) -> bool {
+        self.unmake_null_move()
+    }
+
+    #[pyo3(name = "is_legal")]
+    fn py_is_legal(&self, from: &Coord, to: &Coord) -> bool {
+        self.is_legal(from, to)
+    }
+
+    #[pyo3(name = "move_piece")]
+    fn py_move_piece(&mut self, from: &Coord, to: &Coord, promote: Option<Piece>) {
+        self.move_piece(from, to, promote)
+    }
+
+    /// Independent copy of the board. Piece moves are shared, so it is cheap.
+    #[pyo3(name = "copy")]
+    fn py_copy(&self) -> Self {
+        self.clone()
+    }
+
+    #[pyo3(name = "fork")]
+    fn py_fork(&self) -> Self {
+        self.fork()
+    }
+
+    fn __copy__(&self) -> Self {
+        self.clone()
+    }
+
+    fn __deepcopy__(&self, _memo: &PyAny) -> Self {
+        self.clone()
     }
 
     fn __str__(&self) -> String {
         String::from(self.to_string())
     }
-}
 
-impl std::fmt::Display for Board {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // First Row
+    fn __repr__(&self) -> String {
+        format!("Board('{}')", self.to_fen())
+    }
+
+    fn _repr_svg_(&self) -> String {
+        self.to_svg()
+    }
+
+    fn _repr_html_(&self) -> String {
+        format!("<div>{}</div><pre>{}</pre>", self.to_svg(), self.to_fen())
+    }
+
+    #[pyo3(name = "to_fen")]
+    fn py_to_fen(&self) -> String {
+        self.to_fen()
+    }
+
+    #[pyo3(name = "symmetric_fen")]
+    fn py_symmetric_fen(&self) -> String {
+        self.symmetric_fen()
+    }
+
+    #[pyo3(name = "mirrored")]
+    fn py_mirrored(&self) -> Board {
+        self.mirrored()
+    }
+
+    #[pyo3(name = "to_svg")]
+    fn py_to_svg(&self) -> String {
+        self.to_svg()
+    }
+
+    /// `callback(record, board)` is called after every move, with a copy of
+    /// the board. Its exceptions are printed and don't undo the move.
+    #[pyo3(name = "add_observer")]
+    fn py_add_observer(&mut self, callback: PyObject) {
+        self.add_observer(move |record, board| {
+            Python::with_gil(|py| {
+                if let Err(err) = callback.call1(py, (record.clone(), board.clone())) {
+                    err.print(py);
+                }
+            })
+        });
+    }
 
+    #[pyo3(name = "add_arrow", signature = (from, to, color = ANNOTATION_COLOR))]
+    fn py_add_arrow(&mut self, from: Coord, to: Coord, color: &str) {
+        self.add_arrow(from, to, color);
+    }
+
+    #[pyo3(name = "highlight", signature = (coord, color = ANNOTATION_COLOR))]
+    fn py_highlight(&mut self, coord: Coord, color: &str) {
+        self.highlight(coord, color);
+    }
+
+    #[pyo3(name = "add_label")]
+    fn py_add_label(&mut self, coord: Coord, text: &str) {
+        self.add_label(coord, text);
+    }
+
+    #[pyo3(name = "clear_annotations")]
+    fn py_clear_annotations(&mut self) {
+        self.clear_annotations();
+    }
+
+    #[pyo3(name = "clear_observers")]
+    fn py_clear_observers(&mut self) {
+        self.clear_observers();
+    }
+
+    fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> PyObject {
+        match op {
+            CompareOp::Eq => (self == other).into_py(py),
+            CompareOp::Ne => (self != other).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
+    fn __hash__(&self) -> u64 {
+        self.position_hash()
+    }
+}
+
+impl Board {
+    /// Board with ranks and files, like `Display`, where `empty` draws the
+    /// empty cells
+    pub(super) fn render_with<F>(&self, empty: F) -> String
+    where
+        F: Fn(&Coord) -> String,
+    {
         let mut s = String::from("");
 
         for (i, row) in self.board.iter().enumerate() {
             // row index
             s.push_str(format!("{} ", (i as i32 - self.n_rows as i32).abs()).as_str());
 
-            for piece in row.iter() {
+            for (j, piece) in row.iter().enumerate() {
                 match piece {
                     Some(piece) => s.push_str(&format!("{} ", piece)),
-                    None => s.push_str("· "),
+                    None => s.push_str(&format!(
+                        "{} ",
+                        empty(&Coord {
+                            row: i as i32,
+                            col: j as i32
+                        })
+                    )),
                 };
             }
             s.push_str("\n");
@@ -247,7 +983,18 @@ impl std::fmt::Display for Board {
         }
         s.push_str("\n");
 
-        write!(f, "{}", s)
+        s
+    }
+}
+
+impl std::fmt::Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            self.render_with(|coord| self.annotation_glyph(coord)),
+            self.annotation_lines()
+        )
     }
 }
 
@@ -295,6 +1042,184 @@ mod test {
         assert!(board.is_pawn_row(6, Color::White));
     }
 
+    #[test]
+    fn test_rank_file() {
+        // e2 and e7
+        let e2 = Coord::from_rank_file(1, 4, 8);
+        assert_eq!(e2, Coord { row: 6, col: 4 });
+        assert_eq!(e2.to_rank_file(8), (1, 4));
+        assert_eq!(Coord::from_rank_file(6, 4, 8), Coord { row: 1, col: 4 });
+
+        let board = Board::default();
+        assert_eq!(board.piece_at("e2").unwrap().coord, e2);
+        assert_eq!(board.relative_rank(e2.row, Color::White), 1);
+        assert_eq!(board.relative_rank(e2.row, Color::Black), 6);
+
+        // 10x10: rank 1 is the last row
+        assert_eq!(
+            Coord::from_rank_file(0, 0, 10),
+            Coord { row: 9, col: 0 }
+        );
+        let board = Board::new(Some(10), Some(10));
+        assert!(board.is_pawn_row(8, Color::White));
+        assert!(board.is_promotion_row(9, Color::Black));
+    }
+
+    #[test]
+    fn test_double_step_ranks() {
+        // Horde: pawns on the first rank can move two cells too
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/K3P3 w - - 0 1").unwrap();
+        let e1 = Coord::from_rank_file(0, 4, 8);
+        assert_eq!(board.legal_moves(&e1).len(), 1);
+
+        board.info.double_step_ranks = vec![0, 1];
+        assert_eq!(board.legal_moves(&e1).len(), 2);
+
+        board.push_uci("e1e3").unwrap();
+        assert_eq!(board.info.en_passant, Some(Coord::from_rank_file(1, 4, 8)));
+
+        // a 6x6 board with pawns that start in the first rank
+        let mut board = Board::from_fen("3k2/6/6/1p4/6/P2K2 w - - 0 1").unwrap();
+        board.info.double_step_ranks = vec![0];
+        board.push_uci("a1a3").unwrap();
+        let record = board.push_uci("b3a2").unwrap();
+        assert!(record.en_passant);
+        assert!(board.piece_at("a3").is_none());
+    }
+
+    #[test]
+    fn test_turn() {
+        let board = Board::default();
+
+        // e2 -> e4 (white to move)
+        let white_pawn = Coord { row: 6, col: 4 };
+        assert!(board.can_move(&white_pawn, &Coord { row: 4, col: 4 }, false));
+        assert_eq!(board.get_moves(&white_pawn, false).len(), 2);
+
+        // e7 -> e5 (black, not its turn)
+        let black_pawn = Coord { row: 1, col: 4 };
+        let to = Coord { row: 3, col: 4 };
+        assert!(!board.can_move(&black_pawn, &to, false));
+        assert!(board.get_moves(&black_pawn, false).is_empty());
+
+        assert!(board.can_move(&black_pawn, &to, true));
+        assert_eq!(board.get_moves(&black_pawn, true).len(), 2);
+    }
+
+    #[test]
+    fn test_position_equality() {
+        let board = Board::default();
+        assert_eq!(board, Board::default());
+        assert_eq!(board.position_hash(), Board::default().position_hash());
+
+        // Move counters are not part of the position
+        let other =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 5 20").unwrap();
+        assert_eq!(board, other);
+        assert_eq!(board.position_hash(), other.position_hash());
+
+        // Castling rights order does not matter
+        let other =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w QKqk - 0 1").unwrap();
+        assert_eq!(board, other);
+        assert_eq!(board.position_hash(), other.position_hash());
+    }
+
+    #[test]
+    fn test_position_inequality() {
+        let board = Board::default();
+
+        // Side to move
+        let other =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1").unwrap();
+        assert_ne!(board, other);
+        assert_ne!(board.position_hash(), other.position_hash());
+
+        // Castling rights
+        let other =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w Kkq - 0 1").unwrap();
+        assert_ne!(board, other);
+
+        // En passant
+        let other =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e3 0 1").unwrap();
+        assert_ne!(board, other);
+
+        // Pieces
+        let mut other = Board::default();
+        other.move_to_coord(&Coord { row: 6, col: 4 }, &Coord { row: 4, col: 4 });
+        assert_ne!(board, other);
+        assert_ne!(board.position_hash(), other.position_hash());
+    }
+
+    #[test]
+    fn test_piece_at() {
+        let board = Board::from_fen("4k3/8/8/8/4P3/8/8/R3K2R w KQ - 0 1").unwrap();
+
+        let pawn = board.piece_at("e4").unwrap();
+        assert_eq!((pawn.piece, pawn.color), (PieceType::Pawn, Color::White));
+        assert!(board.piece_at("e5").is_none());
+        assert!(board.piece_at("z9").is_none());
+
+        assert_eq!(board.occupied_squares().count(), 5);
+        assert_eq!(board.empty_squares().count(), 59);
+        assert!(board
+            .empty_squares()
+            .all(|c| matches!(board.get_piece(&c), Ok(None))));
+
+        let rooks: Vec<Coord> = board
+            .pieces_of(Color::White, PieceType::Rook)
+            .map(|rook| rook.coord)
+            .collect();
+        assert_eq!(rooks, [Coord { row: 7, col: 0 }, Coord { row: 7, col: 7 }]);
+        assert_eq!(board.pieces_of(Color::Black, PieceType::Rook).count(), 0);
+    }
+
+    #[test]
+    fn test_to_array() {
+        let board = Board::default();
+        let pieces = board.to_array();
+        let colors = board.color_array();
+
+        assert_eq!(pieces[0], [4, 2, 3, 5, 6, 3, 2, 4]);
+        assert_eq!(pieces[6], [1; 8]);
+        assert_eq!(pieces[4], [0; 8]);
+        assert_eq!((colors[0][0], colors[4][0], colors[7][0]), (-1, 0, 1));
+    }
+
+    #[test]
+    fn test_fork() {
+        let board = Board::default();
+        let mut child = board.fork();
+
+        let e2 = Coord { row: 6, col: 4 };
+        let e4 = Coord { row: 4, col: 4 };
+        child.move_to_coord(&e2, &e4);
+
+        assert!(board.get_piece(&e2).unwrap().is_some());
+        assert!(child.get_piece(&e4).unwrap().is_some());
+
+        // only the modified rows were copied
+        assert!(Arc::ptr_eq(&board.board[0], &child.board[0]));
+        assert!(!Arc::ptr_eq(&board.board[6], &child.board[6]));
+    }
+
+    #[test]
+    fn test_share_between_threads() {
+        let board = std::sync::Arc::new(Board::default());
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let board = board.clone();
+                std::thread::spawn(move || board.get_moves(&Coord { row: 7, col: 6 }, false).len())
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 2);
+        }
+    }
+
     #[test]
     fn test_prom_row() {
         let board = Board::default();
@@ -1,3 +1,4 @@
+use crate::notation::fen::THREE_CHECK_LIMIT;
 use crate::piece::Color;
 use std::collections::HashMap;
 
@@ -22,6 +23,19 @@ pub struct BoardInfo {
 
     /// Fullmove number - the number of the full move. It starts at 1, and is incremented after Black's move.
     pub fullmove_number: i32,
+
+    /// Checks given by each color, only counted if there is a `check_limit`
+    pub checks: HashMap<Color, u32>,
+
+    /// Three-check: the first color to give this many checks wins. FEN only
+    /// stores the remaining checks of three-check, so other limits are not
+    /// written (see `three_check`).
+    pub check_limit: Option<u32>,
+
+    /// Ranks, counted from 0 from each side (see `Board::relative_rank`), where
+    /// pawns can move two cells, e.g. `[0, 1]` in Horde. The second rank by
+    /// default.
+    pub double_step_ranks: Vec<i32>,
 }
 
 impl BoardInfo {
@@ -32,6 +46,18 @@ impl BoardInfo {
             en_passant: None,
             halfmove_clock: 0,
             fullmove_number: 1,
+            checks: HashMap::new(),
+            check_limit: None,
+            double_step_ranks: vec![1],
+        }
+    }
+
+    /// Same as `default`, for a three-check game. Other limits are left out
+    /// as they can't be read back from the FEN's "W+B" remaining checks.
+    pub fn three_check() -> Self {
+        Self {
+            check_limit: Some(THREE_CHECK_LIMIT),
+            ..Self::default()
         }
     }
 
@@ -51,9 +77,30 @@ impl BoardInfo {
     pub fn reset_halfmove_clock(&mut self) {
         self.halfmove_clock = 0;
     }
+
+    pub fn checks_given(&self, color: Color) -> u32 {
+        self.checks.get(&color).copied().unwrap_or(0)
+    }
+
+    /// Checks `color` still has to give to win, if there is a `check_limit`
+    pub fn remaining_checks(&self, color: Color) -> Option<u32> {
+        self.check_limit
+            .map(|limit| limit.saturating_sub(self.checks_given(color)))
+    }
+
+    pub fn add_check(&mut self, color: Color) {
+        *self.checks.entry(color).or_insert(0) += 1;
+    }
+
+    /// Color that reached the `check_limit`, ending the game
+    pub fn check_limit_winner(&self) -> Option<Color> {
+        [Color::White, Color::Black]
+            .into_iter()
+            .find(|color| self.remaining_checks(*color) == Some(0))
+    }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct CastlingRights {
     /// Cell where the king will move to
     pub new_king: Coord,
@@ -0,0 +1,301 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use super::{Board, CastlingRights, Coord, MoveCoords};
+use crate::notation::FenError;
+use crate::piece::{Color, Piece};
+
+impl Board {
+    /// Equivalent position where the side to move is always White.
+    ///
+    /// If Black is to move, the board is flipped vertically (the files are kept,
+    /// so castling sides do not change) and the colors are swapped, together
+    /// with the castling rights and the en passant cell. Move counters are kept.
+    ///
+    /// Use `canonical_coord` / `canonical_move` (on the original board) to
+    /// translate cells and moves between both boards.
+    pub fn canonical(&self) -> Board {
+        if self.is_canonical() {
+            return self.clone();
+        }
+
+        let mut board = Board::new(Some(self.get_rows()), Some(self.get_cols()));
+
+        for color in [self.info.turn, self.info.turn.opposite()] {
+            for piece in self.get_all_pieces(&color) {
+                board.set_piece(
+                    Piece::new(
+                        color.opposite(),
+                        piece.piece,
+                        piece.moves.clone(),
+                        self.canonical_coord(&piece.coord),
+                    )
+                    .with_royal(piece.royal),
+                );
+            }
+        }
+
+        let castling: HashMap<_, Vec<CastlingRights>> = self
+            .info
+            .castling
+            .iter()
+            .map(|(color, rights)| {
+                let rights = rights
+                    .iter()
+                    .map(|right| CastlingRights {
+                        new_king: self.canonical_coord(&right.new_king),
+                        rook: self.canonical_coord(&right.rook),
+                    })
+                    .collect();
+                (color.opposite(), rights)
+            })
+            .collect();
+
+        board.info = self.info.clone();
+        board.info.turn = self.info.turn.opposite();
+        board.info.castling = castling;
+        board.info.checks = self
+            .info
+            .checks
+            .iter()
+            .map(|(color, checks)| (color.opposite(), *checks))
+            .collect();
+        board.info.en_passant = self.info.en_passant.map(|c| self.canonical_coord(&c));
+
+        board
+    }
+
+    /// True if White is to move, so `canonical` is the board itself
+    pub fn is_canonical(&self) -> bool {
+        self.info.turn == Color::White
+    }
+
+    /// Translates a cell of this board into the canonical board and back (the
+    /// mapping is its own inverse)
+    pub fn canonical_coord(&self, coord: &Coord) -> Coord {
+        match self.is_canonical() {
+            true => *coord,
+            false => Coord {
+                row: self.get_rows() as i32 - 1 - coord.row,
+                col: coord.col,
+            },
+        }
+    }
+
+    /// Translates a move of this board into the canonical board and back
+    pub fn canonical_move(&self, (from, to): MoveCoords) -> MoveCoords {
+        (self.canonical_coord(&from), self.canonical_coord(&to))
+    }
+}
+
+/// Positions of a corpus that are the same up to color and file symmetry,
+/// see `duplicate_report`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DuplicateReport {
+    pub total: usize,
+    pub unique: usize,
+}
+
+impl DuplicateReport {
+    /// Share of the positions that repeat an earlier one, from 0 to 1
+    pub fn duplicate_ratio(&self) -> f64 {
+        match self.total {
+            0 => 0.0,
+            total => (total - self.unique) as f64 / total as f64,
+        }
+    }
+}
+
+/// Counts the distinct positions of a set of FENs by their `symmetric_fen`
+pub fn duplicate_report(fens: &[&str]) -> Result<DuplicateReport, FenError> {
+    let mut unique = HashSet::new();
+    for fen in fens {
+        unique.insert(Board::from_fen(fen)?.symmetric_fen());
+    }
+
+    Ok(DuplicateReport {
+        total: fens.len(),
+        unique: unique.len(),
+    })
+}
+
+/// Indices of the first occurrence of each position of `boards`, dropping
+/// the repeats. Positions are keyed by `position_hash`, or by `symmetric_fen`
+/// with `symmetric` so that transpositions up to colors and mirroring count
+/// as repeats too.
+pub fn dedup_positions(boards: &[Board], symmetric: bool) -> Vec<usize> {
+    let mut seen = HashSet::new();
+    let mut kept = vec![];
+
+    for (i, board) in boards.iter().enumerate() {
+        let key = match symmetric {
+            true => {
+                let mut hasher = DefaultHasher::new();
+                board.symmetric_fen().hash(&mut hasher);
+                hasher.finish()
+            }
+            false => board.position_hash(),
+        };
+        if seen.insert(key) {
+            kept.push(i);
+        }
+    }
+
+    kept
+}
+
+impl Board {
+    /// The board flipped horizontally: a-file pieces go to the h-file. Castling
+    /// rights are mirrored too, though they are usually not symmetric.
+    pub fn mirrored(&self) -> Board {
+        let col = |coord: &Coord| Coord {
+            row: coord.row,
+            col: self.get_cols() as i32 - 1 - coord.col,
+        };
+
+        let mut board = self.fork();
+        for (coord, _) in self.cells() {
+            board.remove_piece(&coord);
+        }
+        for (coord, piece) in self.cells() {
+            if let Some(piece) = piece {
+                let mut piece = piece.clone();
+                piece.coord = col(&coord);
+                board.set_piece(piece);
+            }
+        }
+
+        for rights in board.info.castling.values_mut() {
+            for right in rights.iter_mut() {
+                right.new_king = col(&right.new_king);
+                right.rook = col(&right.rook);
+            }
+        }
+        board.info.en_passant = self.info.en_passant.map(|c| col(&c));
+
+        board
+    }
+
+    /// FEN of the position without move counters, the same for all the
+    /// positions equal up to colors (see `canonical`) and, if there are no
+    /// castling rights, up to mirroring the files
+    pub fn symmetric_fen(&self) -> String {
+        let mut canonical = self.canonical();
+        canonical.info.halfmove_clock = 0;
+        canonical.info.fullmove_number = 1;
+        let fen = canonical.to_fen();
+
+        let castling = canonical.info.castling.values().any(|r| !r.is_empty());
+        match castling {
+            true => fen,
+            false => std::cmp::min(fen, canonical.mirrored().to_fen()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_white_to_move() {
+        let board = Board::default();
+        assert!(board.is_canonical());
+        assert!(board.canonical() == board);
+
+        let e2 = Coord { row: 6, col: 4 };
+        assert_eq!(board.canonical_coord(&e2), e2);
+    }
+
+    #[test]
+    fn test_black_to_move() {
+        let board =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b Kq e3 0 1").unwrap();
+        let canonical = board.canonical();
+
+        // same position, seen from the black side
+        let expected =
+            Board::from_fen("rnbqkbnr/pppp1ppp/8/4p3/8/8/PPPPPPPP/RNBQKBNR w Qk e6 0 1").unwrap();
+        assert!(canonical == expected);
+        assert_eq!(canonical.info.turn, Color::White);
+
+        // e7-e5 is e2-e4 in the canonical board, and back
+        let e7e5 = (Coord { row: 1, col: 4 }, Coord { row: 3, col: 4 });
+        let e2e4 = (Coord { row: 6, col: 4 }, Coord { row: 4, col: 4 });
+        assert_eq!(board.canonical_move(e7e5), e2e4);
+        assert_eq!(board.canonical_move(e2e4), e7e5);
+        assert!(canonical.can_move(&e2e4.0, &e2e4.1, false));
+
+        // moves are the same, up to the mapping
+        let mut moves: Vec<MoveCoords> = board
+            .move_gen(None, &[])
+            .map(|m| board.canonical_move(m))
+            .collect();
+        let mut canonical_moves: Vec<MoveCoords> = canonical.move_gen(None, &[]).collect();
+        let key = |(from, to): &MoveCoords| (from.row, from.col, to.row, to.col);
+        moves.sort_by_key(key);
+        canonical_moves.sort_by_key(key);
+        assert_eq!(moves, canonical_moves);
+    }
+
+    #[test]
+    fn test_three_check() {
+        // White needs 1 more check and Black 3: the colors swap
+        let board = Board::from_fen("4k3/8/8/8/8/8/1P6/4K3 b - - 1+3 0 1").unwrap();
+        let canonical = board.canonical();
+
+        assert_eq!(canonical.to_fen(), "4k3/1p6/8/8/8/8/8/4K3 w - - 3+1 0 1");
+        assert_eq!(canonical.info.checks_given(Color::Black), 2);
+        assert_eq!(canonical.info.remaining_checks(Color::White), Some(3));
+    }
+
+    #[test]
+    fn test_duplicate_report() {
+        let fens = [
+            "4k3/8/8/8/8/8/1P6/4K3 w - - 0 1",
+            // mirrored, later in the game
+            "3k4/8/8/8/8/8/6P1/3K4 w - - 4 30",
+            // colors swapped
+            "4k3/1p6/8/8/8/8/8/4K3 b - - 0 1",
+            "4k3/8/8/8/8/8/1P6/4K2R w - - 0 1",
+            "r3k3/8/8/8/8/8/8/R3K3 w Qq - 0 1",
+        ];
+        let report = duplicate_report(&fens).unwrap();
+        assert_eq!(
+            report,
+            DuplicateReport {
+                total: 5,
+                unique: 3
+            }
+        );
+        assert_eq!(report.duplicate_ratio(), 0.4);
+
+        let board = Board::from_fen(fens[1]).unwrap();
+        assert!(board.mirrored().mirrored() == board);
+
+        // castling rights break the mirror symmetry
+        let board = Board::from_fen(fens[4]).unwrap();
+        assert_eq!(board.symmetric_fen(), fens[4]);
+        assert!(duplicate_report(&["8/8"]).is_err());
+    }
+
+    #[test]
+    fn test_dedup_positions() {
+        let fens = [
+            "4k3/8/8/8/8/8/1P6/4K3 w - - 0 1",
+            // same position, later in the game
+            "4k3/8/8/8/8/8/1P6/4K3 w - - 4 30",
+            // mirrored
+            "3k4/8/8/8/8/8/6P1/3K4 w - - 0 1",
+            // colors swapped
+            "4k3/1p6/8/8/8/8/8/4K3 b - - 0 1",
+            "4k3/8/8/8/8/8/1P6/4K2R w - - 0 1",
+        ];
+        let boards: Vec<Board> = fens.iter().map(|f| Board::from_fen(f).unwrap()).collect();
+
+        assert_eq!(dedup_positions(&boards, false), [0, 2, 3, 4]);
+        assert_eq!(dedup_positions(&boards, true), [0, 4]);
+        assert!(dedup_positions(&[], true).is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+use super::{Board, Coord};
+use crate::piece::{Color, PieceType};
+
+impl Board {
+    /// Problems found in the board state, empty if it is consistent:
+    /// - Every piece knows the cell it is in.
+    /// - At most one king per color.
+    /// - Castling rights point to a rook of their color, with its king in the
+    ///   same row.
+    /// - The en passant cell is empty, with the pawn that skipped it right in
+    ///   front of it.
+    /// - The position hash doesn't depend on how the board was copied.
+    pub fn check_consistency(&self) -> Vec<String> {
+        let mut problems = vec![];
+
+        for row in 0..self.get_rows() as i32 {
+            for col in 0..self.get_cols() as i32 {
+                let coord = Coord { row, col };
+                if let Ok(Some(piece)) = self.get_piece(&coord) {
+                    if piece.coord != coord {
+                        problems.push(format!(
+                            "{:?} in {:?} thinks it is in {:?}",
+                            piece.piece, coord, piece.coord
+                        ));
+                    }
+                }
+            }
+        }
+
+        for color in [Color::White, Color::Black] {
+            let pieces = self.get_all_pieces(&color);
+            let kings: Vec<Coord> = pieces
+                .iter()
+                .filter(|piece| piece.piece == PieceType::King)
+                .map(|piece| piece.coord)
+                .collect();
+            if kings.len() > 1 {
+                problems.push(format!("{:?} has {} kings", color, kings.len()));
+            }
+
+            for right in self.info.castling.get(&color).into_iter().flatten() {
+                let rook = pieces
+                    .iter()
+                    .any(|piece| piece.coord == right.rook && piece.piece == PieceType::Rook);
+                if !rook {
+                    problems.push(format!(
+                        "{:?} can castle with a missing rook in {:?}",
+                        color, right.rook
+                    ));
+                }
+                if !kings.iter().any(|king| king.row == right.rook.row) {
+                    problems.push(format!(
+                        "{:?} can castle with {:?} without a king in its row",
+                        color, right.rook
+                    ));
+                }
+            }
+        }
+
+        if let Some(en_passant) = self.info.en_passant {
+            // the pawn moved last, so it is not from the side to move
+            let color = self.info.turn.opposite();
+            let pawn = Coord {
+                row: match color {
+                    Color::White => en_passant.row - 1,
+                    Color::Black => en_passant.row + 1,
+                },
+                col: en_passant.col,
+            };
+
+            let empty = matches!(self.get_piece(&en_passant), Ok(None));
+            let pawn_there = matches!(
+                self.get_piece(&pawn),
+                Ok(Some(piece)) if piece.piece == PieceType::Pawn && piece.color == color
+            );
+            if !empty || !pawn_there {
+                problems.push(format!(
+                    "En passant in {:?} without a {:?} pawn that skipped it",
+                    en_passant, color
+                ));
+            }
+        }
+
+        if self.position_hash() != self.fork().position_hash() {
+            problems.push("The position hash changes when the board is forked".to_string());
+        }
+
+        problems
+    }
+
+    /// Panics with the problems found by `check_consistency`, if any.
+    ///
+    /// With the `debug-checks` feature, it runs after every `push` and `pop`.
+    pub fn assert_consistent(&self) {
+        let problems = self.check_consistency();
+        assert!(
+            problems.is_empty(),
+            "Inconsistent board:\n{}\n{}",
+            problems.join("\n"),
+            self
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consistent() {
+        Board::default().assert_consistent();
+
+        let mut board = Board::default();
+        for uci in ["e2e4", "d7d5", "e4d5", "e7e5", "d5e6", "g8f6"] {
+            board.push_uci(uci).unwrap();
+            board.assert_consistent();
+        }
+        while board.pop().is_some() {
+            board.assert_consistent();
+        }
+    }
+
+    #[test]
+    fn test_inconsistent() {
+        // two white kings, castling without rooks and an en passant cell
+        // without a pawn
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/3KK3 b KQ e3 0 1").unwrap();
+        let problems = board.check_consistency();
+        assert_eq!(problems.len(), 4, "{:?}", problems);
+    }
+}
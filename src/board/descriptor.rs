@@ -0,0 +1,155 @@
+#[cfg(feature = "python")]
+use pyo3::{prelude::*, pyclass::CompareOp};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use super::{Board, Coord};
+use crate::piece::{Color, PieceType};
+
+/// Coarse summary of a position, to group or stratify positions without
+/// comparing whole boards. Positions with the same pawns, material and kings
+/// have equal descriptors, whatever the other pieces' cells.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "python", pyclass)]
+pub struct PositionDescriptor {
+    /// Hash of the cells of the pawns of both colors
+    pub pawn_hash: u64,
+    /// Pieces of each side, strongest first, e.g. "KRPPvKNP"
+    pub material: String,
+    pub white_king: Option<Coord>,
+    pub black_king: Option<Coord>,
+}
+
+/// Order of the pieces in the material signature, custom pieces go last
+fn strength_rank(piece: PieceType) -> usize {
+    match piece {
+        PieceType::King => 0,
+        PieceType::Queen => 1,
+        PieceType::Rook => 2,
+        PieceType::Bishop => 3,
+        PieceType::Knight => 4,
+        PieceType::Pawn => 5,
+        PieceType::Custom(_) => 6,
+    }
+}
+
+impl PositionDescriptor {
+    /// Share of the four features (pawns, material and each king) that are
+    /// equal in both positions, from 0 to 1
+    pub fn similarity(&self, other: &Self) -> f64 {
+        let same = [
+            self.pawn_hash == other.pawn_hash,
+            self.material == other.material,
+            self.white_king == other.white_king,
+            self.black_king == other.black_king,
+        ];
+        same.iter().filter(|same| **same).count() as f64 / same.len() as f64
+    }
+
+    /// Single hash of the descriptor, e.g. to use it as a bucket key
+    pub fn key(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl Board {
+    pub fn descriptor(&self) -> PositionDescriptor {
+        let mut hasher = DefaultHasher::new();
+        for (coord, piece) in self.cells() {
+            if let Some(piece) = piece.filter(|piece| piece.piece == PieceType::Pawn) {
+                (coord, piece.color).hash(&mut hasher);
+            }
+        }
+
+        let material: Vec<String> = [Color::White, Color::Black]
+            .iter()
+            .map(|color| {
+                let mut pieces: Vec<PieceType> = self
+                    .get_all_pieces(color)
+                    .iter()
+                    .map(|piece| piece.piece)
+                    .collect();
+                pieces.sort_by_key(|piece| (strength_rank(*piece), piece.to_char()));
+                pieces
+                    .iter()
+                    .map(|piece| piece.to_char().to_ascii_uppercase())
+                    .collect()
+            })
+            .collect();
+
+        PositionDescriptor {
+            pawn_hash: hasher.finish(),
+            material: material.join("v"),
+            white_king: self.king_coord(&Color::White),
+            black_king: self.king_coord(&Color::Black),
+        }
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl PositionDescriptor {
+    #[getter(pawn_hash)]
+    fn py_pawn_hash(&self) -> u64 {
+        self.pawn_hash
+    }
+
+    #[getter(material)]
+    fn py_material(&self) -> String {
+        self.material.clone()
+    }
+
+    #[getter(white_king)]
+    fn py_white_king(&self) -> Option<Coord> {
+        self.white_king
+    }
+
+    #[getter(black_king)]
+    fn py_black_king(&self) -> Option<Coord> {
+        self.black_king
+    }
+
+    #[pyo3(name = "similarity")]
+    fn py_similarity(&self, other: &Self) -> f64 {
+        self.similarity(other)
+    }
+
+    fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> PyObject {
+        match op {
+            CompareOp::Eq => (self == other).into_py(py),
+            CompareOp::Ne => (self != other).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
+    fn __hash__(&self) -> u64 {
+        self.key()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_descriptor() {
+        let board = Board::from_fen("4k3/2n2p2/8/8/8/8/3PP3/R3K3 w - - 0 1").unwrap();
+        let descriptor = board.descriptor();
+        assert_eq!(descriptor.material, "KRPPvKNP");
+        assert_eq!(descriptor.white_king, Some(Coord { row: 7, col: 4 }));
+
+        // only the knight moved
+        let other = Board::from_fen("4k3/5p2/8/3n4/8/8/3PP3/R3K3 w - - 0 1").unwrap();
+        assert_eq!(other.descriptor(), descriptor);
+        assert_eq!(other.descriptor().key(), descriptor.key());
+
+        // without the knight, and a pawn and the black king moved
+        let other = Board::from_fen("8/4k3/5p2/8/8/8/3PP3/R3K3 w - - 0 1").unwrap();
+        let other = other.descriptor();
+        assert_ne!(other.pawn_hash, descriptor.pawn_hash);
+        assert_eq!(other.similarity(&descriptor), 0.25);
+        assert_eq!(descriptor.similarity(&descriptor), 1.0);
+    }
+}
@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use super::{Board, MoveRecord};
+
+/// Rule of a variant run right after the pieces of a move are moved, e.g. the
+/// explosions of atomic chess. It may change the cells and `info` of the
+/// board, which `pop` restores like the rest of the move.
+pub type MoveEffect = Arc<dyn Fn(&MoveRecord, &mut Board) + Send + Sync>;
+
+/// Unlike observers, effects are part of the rules: clones and forks keep
+/// them, so legality checks on copies see the same moves.
+#[derive(Clone, Default)]
+pub(super) struct MoveEffects(Vec<MoveEffect>);
+
+impl MoveEffects {
+    pub(super) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Board {
+    /// Registers a side effect of every move played on this board (and its
+    /// copies), applied in the order they were added
+    pub fn add_move_effect<F>(&mut self, effect: F)
+    where
+        F: Fn(&MoveRecord, &mut Board) + Send + Sync + 'static,
+    {
+        self.effects.0.push(Arc::new(effect));
+        self.clear_cache();
+    }
+
+    pub fn clear_move_effects(&mut self) {
+        self.effects.0.clear();
+        self.clear_cache();
+    }
+
+    pub(super) fn apply_effects(&mut self, record: &MoveRecord) {
+        for effect in self.effects.0.clone() {
+            effect(record, self);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Coord;
+    use crate::piece::{Color, PieceType};
+
+    /// Atomic chess: captures explode, removing the capturing piece and the
+    /// pieces around, but not pawns
+    fn explode(record: &MoveRecord, board: &mut Board) {
+        if record.captured.is_none() {
+            return;
+        }
+
+        board.remove_piece(&record.to);
+        for row in -1..=1 {
+            for col in -1..=1 {
+                let coord = Coord {
+                    row: record.to.row + row,
+                    col: record.to.col + col,
+                };
+                if let Ok(Some(piece)) = board.get_piece(&coord) {
+                    if piece.piece != PieceType::Pawn {
+                        board.remove_piece(&coord);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_move_effects() {
+        let fen = "4k3/8/2n5/3p4/8/8/3Q4/4K3 w - - 0 1";
+        let mut board = Board::from_fen(fen).unwrap();
+        board.add_move_effect(explode);
+
+        board.push_uci("d2d5").unwrap();
+        assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/4K3 b - - 0 1");
+        board.pop();
+        assert_eq!(board.to_fen(), fen);
+
+        // copies play by the same rules
+        let mut fork = board.fork();
+        fork.push_uci("d2d5").unwrap();
+        assert_eq!(fork.get_all_pieces(&Color::White).len(), 1);
+
+        board.clear_move_effects();
+        board.push_uci("d2d5").unwrap();
+        assert_eq!(board.to_fen(), "4k3/8/2n5/3Q4/8/8/8/4K3 b - - 0 1");
+    }
+}
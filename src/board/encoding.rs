@@ -0,0 +1,317 @@
+use super::{Board, Coord, MoveError, MoveRecord};
+use crate::piece::PieceType;
+
+/// A move with its promotion piece, if any
+pub type FullMove = (Coord, Coord, Option<PieceType>);
+
+pub(super) const PROMOTIONS: [PieceType; 4] = [
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+];
+
+fn promotion_rank(promotion: Option<PieceType>) -> usize {
+    match promotion {
+        None => 0,
+        Some(piece) => 1 + PROMOTIONS.iter().position(|p| *p == piece).unwrap_or(4),
+    }
+}
+
+impl Board {
+    /// Legal moves of the side to move, one per promotion piece, sorted by
+    /// origin cell, destination cell (both in `Coord` order) and then
+    /// promotion (queen, rook, bishop, knight).
+    ///
+    /// The order only depends on the position, so the i-th move is the same on
+    /// every run and platform. It is part of the API: encoded games and move
+    /// indices rely on it.
+    pub fn sorted_legal_moves(&self) -> Vec<FullMove> {
+        let mut moves = vec![];
+        let info = self.check_info();
+
+        for piece in self.get_all_pieces(&self.info.turn) {
+            let from = piece.coord;
+            let pawn = piece.piece == PieceType::Pawn;
+
+            for to in self.legal_moves_with(&info, &from) {
+                match pawn && self.is_promotion_row(to.row, piece.color) {
+                    true => moves.extend(PROMOTIONS.iter().map(|p| (from, to, Some(*p)))),
+                    false => moves.push((from, to, None)),
+                }
+            }
+        }
+
+        moves.sort_by_key(|(from, to, promotion)| (*from, *to, promotion_rank(*promotion)));
+        moves
+    }
+
+    /// Index of a move in `sorted_legal_moves`, None if it is not legal
+    pub fn legal_move_index(&self, full_move: &FullMove) -> Option<usize> {
+        self.sorted_legal_moves()
+            .iter()
+            .position(|m| m == full_move)
+    }
+
+    /// Encodes a game played from this position as the index of each move in
+    /// `sorted_legal_moves`, written as a LEB128 varint (one byte for the
+    /// first 128 moves). Null moves are the index past the last legal move.
+    pub fn encode_moves(&self, moves: &[MoveRecord]) -> Result<Vec<u8>, MoveError> {
+        let mut board = self.fork();
+        let mut bytes = vec![];
+
+        for record in moves {
+            let legal = board.sorted_legal_moves();
+
+            let index = match record.is_null() {
+                true => {
+                    board.make_null_move();
+                    legal.len()
+                }
+                false => {
+                    let full_move = (record.from, record.to, record.promotion);
+                    let index = legal.iter().position(|m| *m == full_move).ok_or_else(|| {
+                        MoveError::IllegalMove(format!("{} can't be played", record.uci))
+                    })?;
+                    board.push(&record.from, &record.to, record.promotion)?;
+                    index
+                }
+            };
+
+            write_varint(&mut bytes, index);
+        }
+
+        Ok(bytes)
+    }
+
+    /// `encode_moves` of the moves played on this board since `start_position`.
+    ///
+    /// Each move is indexed in the position it was played on, so the board can
+    /// be edited between moves (`set_piece`...). `push_encoded` only replays
+    /// the game from `start_position` if it wasn't.
+    pub fn encode_game(&self) -> Result<Vec<u8>, MoveError> {
+        let mut bytes = vec![];
+
+        for (board, record) in self.history_boards() {
+            let legal = board.sorted_legal_moves();
+            let index = match record.is_null() {
+                true => legal.len(),
+                false => {
+                    let full_move = (record.from, record.to, record.promotion);
+                    legal.iter().position(|m| *m == full_move).ok_or_else(|| {
+                        MoveError::IllegalMove(format!("{} can't be played", record.uci))
+                    })?
+                }
+            };
+
+            write_varint(&mut bytes, index);
+        }
+
+        Ok(bytes)
+    }
+
+    /// Plays the moves of an `encode_moves` byte stream. If it is not valid,
+    /// the board is left as it was.
+    pub fn push_encoded(&mut self, bytes: &[u8]) -> Result<Vec<MoveRecord>, MoveError> {
+        let mut records = vec![];
+        let mut bytes = bytes.iter();
+
+        let result = loop {
+            let index = match read_varint(&mut bytes) {
+                Ok(Some(index)) => index,
+                Ok(None) => break Ok(()),
+                Err(err) => break Err(err),
+            };
+
+            let legal = self.sorted_legal_moves();
+            let record = match legal.get(index) {
+                Some((from, to, promotion)) => self.push(from, to, *promotion),
+                None if index == legal.len() => Ok(self.make_null_move()),
+                None => Err(MoveError::InvalidEncoding(format!(
+                    "Move index {} out of {} moves",
+                    index,
+                    legal.len()
+                ))),
+            };
+
+            match record {
+                Ok(record) => records.push(record),
+                Err(err) => break Err(err),
+            }
+        };
+
+        if let Err(err) = result {
+            for _ in &records {
+                self.pop();
+            }
+            return Err(err);
+        }
+
+        Ok(records)
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Next value of the stream, None if it is over
+fn read_varint<'a, I>(bytes: &mut I) -> Result<Option<usize>, MoveError>
+where
+    I: Iterator<Item = &'a u8>,
+{
+    let mut value = 0;
+    let mut shift = 0;
+
+    for byte in bytes {
+        if shift >= usize::BITS {
+            break;
+        }
+        value |= ((byte & 0x7f) as usize) << shift;
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            return Ok(Some(value));
+        }
+    }
+
+    match shift {
+        0 => Ok(None),
+        _ => Err(MoveError::InvalidEncoding(
+            "Truncated move index".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piece::{Color, Piece};
+
+    #[test]
+    fn test_sorted_legal_moves() {
+        let board = Board::default();
+        let moves = board.sorted_legal_moves();
+        assert_eq!(moves.len(), 20);
+        // a2-a4: a2 is the first cell with a white piece, a4 is before a3
+        assert_eq!(
+            moves[0],
+            (Coord { row: 6, col: 0 }, Coord { row: 4, col: 0 }, None)
+        );
+
+        let board = Board::from_fen("8/4P3/8/8/8/8/k7/7K w - - 0 1").unwrap();
+        let promotions: Vec<_> = board
+            .sorted_legal_moves()
+            .into_iter()
+            .filter_map(|(_, _, promotion)| promotion)
+            .collect();
+        assert_eq!(promotions, PROMOTIONS);
+    }
+
+    #[test]
+    fn test_ordering_is_stable() {
+        let board = Board::from_fen("r3k2r/1P6/8/3pP3/8/8/8/R3K1N1 w Qkq d6 0 1").unwrap();
+        let uci: Vec<String> = board
+            .sorted_legal_moves()
+            .iter()
+            .map(|(from, to, promotion)| board.move_to_uci(from, to, *promotion).unwrap())
+            .collect();
+
+        // Changing this order breaks encoded games
+        assert_eq!(
+            uci,
+            [
+                "b7a8q", "b7a8r", "b7a8b", "b7a8n", "b7b8q", "b7b8r", "b7b8b", "b7b8n", "e5d6",
+                "e5e6", "a1a8", "a1a7", "a1a6", "a1a5", "a1a4", "a1a3", "a1a2", "a1b1", "a1c1",
+                "a1d1", "e1d2", "e1e2", "e1f2", "e1c1", "e1d1", "e1f1", "g1f3", "g1h3", "g1e2"
+            ]
+        );
+        assert_eq!(
+            board.legal_move_index(&board.parse_uci("e5d6").unwrap()),
+            Some(8)
+        );
+        assert_eq!(
+            board.legal_move_index(&board.parse_uci("e1g1").unwrap()),
+            None
+        );
+
+        for _ in 0..10 {
+            assert_eq!(
+                board.fork().sorted_legal_moves(),
+                board.sorted_legal_moves()
+            );
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut board = Board::default();
+        for uci in [
+            "e2e4", "d7d5", "e4d5", "g8f6", "f1b5", "c7c6", "d5c6", "d8d2", "b1d2",
+        ] {
+            board.push_uci(uci).unwrap();
+        }
+        board.make_null_move();
+        for uci in ["c6b7", "b8d7", "b7a8n"] {
+            board.push_uci(uci).unwrap();
+        }
+
+        let bytes = board.encode_game().unwrap();
+        assert_eq!(bytes.len(), 13);
+
+        let mut decoded = Board::default();
+        let records = decoded.push_encoded(&bytes).unwrap();
+        assert_eq!(records.len(), 13);
+        assert!(decoded == board);
+        assert_eq!(records.last().unwrap().uci, "b7a8n");
+        assert!(records[9].is_null());
+
+        // invalid streams leave the board as it was
+        let mut board = Board::default();
+        assert!(board.push_encoded(&[0, 0, 200]).is_err());
+        assert!(board.push_encoded(&[0, 0x80]).is_err());
+        assert!(board == Board::default());
+        assert!(board.move_history().is_empty());
+    }
+
+    #[test]
+    fn test_edited_board() {
+        // A knight put on d4 between moves is not in the start position
+        let mut board = Board::default();
+        board.push_uci("e2e4").unwrap();
+        board.push_uci("e7e5").unwrap();
+        board.set_piece(Piece::new_knight(
+            Color::White,
+            Coord::from_rank_file(3, 3, 8),
+        ));
+        board.push_uci("d4f5").unwrap();
+
+        let bytes = board.encode_game().unwrap();
+        assert_eq!(bytes.len(), 3);
+        assert_eq!(
+            bytes[2] as usize,
+            board.history_boards()[2]
+                .0
+                .legal_move_index(&(
+                    Coord::from_rank_file(3, 3, 8),
+                    Coord::from_rank_file(4, 5, 8),
+                    None
+                ))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_varint() {
+        for value in [0, 1, 127, 128, 300, 70000] {
+            let mut bytes = vec![];
+            write_varint(&mut bytes, value);
+            assert_eq!(read_varint(&mut bytes.iter()), Ok(Some(value)));
+        }
+        assert_eq!(read_varint(&mut [].iter()), Ok(None));
+    }
+}
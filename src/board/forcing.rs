@@ -0,0 +1,212 @@
+use super::encoding::PROMOTIONS;
+use super::{Board, FullMove};
+use crate::piece::{Color, PieceType};
+
+/// Deepest `forced_mate` search, in moves of the mating side
+pub const MAX_MATE_DEPTH: u32 = 3;
+
+impl Board {
+    /// The move of the side to move, if it has exactly one legal move
+    pub fn only_move(&self) -> Option<FullMove> {
+        match self.sorted_legal_moves().as_slice() {
+            [only] => Some(*only),
+            _ => None,
+        }
+    }
+
+    /// Moves played from this position while the side to move has a single
+    /// legal move, up to `max_plies`. Empty if the first move is not forced.
+    pub fn forced_line(&self, max_plies: usize) -> Vec<FullMove> {
+        let mut board = self.fork();
+        let mut line = vec![];
+
+        while line.len() < max_plies {
+            let (from, to, promotion) = match board.only_move() {
+                Some(only) => only,
+                None => break,
+            };
+            board
+                .push(&from, &to, promotion)
+                .expect("Legal moves can be pushed");
+            line.push((from, to, promotion));
+        }
+
+        line
+    }
+
+    /// Legal moves of `color` that check the other side, directly or by
+    /// discovery, in `sorted_legal_moves` order. Moves of the side not to move
+    /// are generated as if it was their turn.
+    pub fn checking_moves(&self, color: Color) -> Vec<FullMove> {
+        let mut board = self.fork();
+        if color != self.info.turn {
+            board.info.turn = color;
+            board.info.en_passant = None;
+        }
+
+        board
+            .sorted_legal_moves()
+            .into_iter()
+            .filter(|(from, to, promotion)| {
+                let mut after = board.fork();
+                after
+                    .push(from, to, *promotion)
+                    .expect("Legal moves can be pushed");
+                after.is_in_check(color.opposite())
+            })
+            .collect()
+    }
+
+    /// True if the side to move is in check and has no legal move
+    pub fn is_checkmate(&self) -> bool {
+        self.is_in_check(self.info.turn) && !self.has_legal_move()
+    }
+
+    pub fn mate_in_one(&self) -> Option<FullMove> {
+        self.forced_mate(1)
+    }
+
+    /// First move of a mate in at most `depth` moves of the side to move,
+    /// whatever the other side plays. The cost grows fast with the depth, so
+    /// it is capped to `MAX_MATE_DEPTH`.
+    pub fn forced_mate(&self, depth: u32) -> Option<FullMove> {
+        let depth = depth.min(MAX_MATE_DEPTH);
+        if depth == 0 {
+            return None;
+        }
+
+        self.staged_legal_moves()
+            .into_iter()
+            .find(|(from, to, promotion)| {
+                let mut after = self.fork();
+                after
+                    .push(from, to, *promotion)
+                    .expect("Legal moves can be pushed");
+                after.is_mated_within(depth - 1)
+            })
+    }
+
+    /// True if the side to move is mated, now or after any of its moves
+    /// within `depth` more moves of the other side
+    fn is_mated_within(&self, depth: u32) -> bool {
+        if !self.has_legal_move() {
+            return self.is_in_check(self.info.turn);
+        }
+
+        depth > 0
+            && self
+                .staged_legal_moves()
+                .iter()
+                .all(|(from, to, promotion)| {
+                    let mut after = self.fork();
+                    after
+                        .push(from, to, *promotion)
+                        .expect("Legal moves can be pushed");
+                    after.forced_mate(depth).is_some()
+                })
+    }
+
+    /// Stops at the first legal move of the staged generator
+    fn has_legal_move(&self) -> bool {
+        let info = self.check_info();
+        self.move_gen(None, &[])
+            .any(|(from, to)| self.is_legal_with(&info, &from, &to))
+    }
+
+    /// Legal moves of the side to move in `move_gen` order (captures first),
+    /// one per promotion piece
+    fn staged_legal_moves(&self) -> Vec<FullMove> {
+        let mut moves = vec![];
+        let info = self.check_info();
+
+        for (from, to) in self.move_gen(None, &[]) {
+            if !self.is_legal_with(&info, &from, &to) {
+                continue;
+            }
+
+            let promotes = match self.get_piece(&from) {
+                Ok(Some(piece)) => {
+                    piece.piece == PieceType::Pawn && self.is_promotion_row(to.row, piece.color)
+                }
+                _ => false,
+            };
+            match promotes {
+                true => moves.extend(PROMOTIONS.iter().map(|p| (from, to, Some(*p)))),
+                false => moves.push((from, to, None)),
+            }
+        }
+
+        moves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Coord;
+
+    #[test]
+    fn test_only_move() {
+        assert_eq!(Board::default().only_move(), None);
+
+        // the h8 king can only go to h7, then White has many moves
+        let board = Board::from_fen("7k/8/8/8/8/8/8/K5R1 b - - 0 1").unwrap();
+        let (h8, h7) = (Coord { row: 0, col: 7 }, Coord { row: 1, col: 7 });
+        assert_eq!(board.only_move(), Some((h8, h7, None)));
+        assert_eq!(board.forced_line(10), [(h8, h7, None)]);
+        assert_eq!(board.forced_line(0), []);
+
+        // no legal moves: mate or stalemate
+        let board = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        assert_eq!(board.only_move(), None);
+    }
+
+    #[test]
+    fn test_checking_moves() {
+        // any move of the e4 bishop uncovers the e1 rook, and the d5 knight
+        // checks from f6 and c7. The d1 rook is blocked by the knight.
+        let board = Board::from_fen("4k3/8/8/3N4/4B3/8/8/K2RR3 w - - 0 1").unwrap();
+        let moves = board.checking_moves(Color::White);
+        let uci: Vec<String> = moves
+            .iter()
+            .map(|(from, to, promotion)| board.move_to_uci(from, to, *promotion).unwrap())
+            .collect();
+
+        assert!(uci.contains(&"d5f6".to_string()) && uci.contains(&"d5c7".to_string()));
+        assert!(uci.contains(&"e4h7".to_string()));
+        assert_eq!(uci.iter().filter(|m| m.starts_with("e4")).count(), 9);
+        assert_eq!(uci.len(), 11);
+
+        assert!(board.checking_moves(Color::Black).is_empty());
+    }
+
+    #[test]
+    fn test_forced_mate() {
+        let board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let (a1, a8) = (Coord { row: 7, col: 0 }, Coord { row: 0, col: 0 });
+        assert_eq!(board.mate_in_one(), Some((a1, a8, None)));
+
+        let mut mated = board.fork();
+        mated.push(&a1, &a8, None).unwrap();
+        assert!(mated.is_checkmate());
+        assert!(!board.is_checkmate());
+
+        // Rh8+ lets the king out through a7, Kb6 first mates next move
+        let board = Board::from_fen("k7/8/2K5/8/8/8/8/7R w - - 0 1").unwrap();
+        assert_eq!(board.mate_in_one(), None);
+        let first = board.forced_mate(2).unwrap();
+        let mut after = board.fork();
+        after.push(&first.0, &first.1, first.2).unwrap();
+        assert!(after
+            .sorted_legal_moves()
+            .iter()
+            .all(|(from, to, promotion)| {
+                let mut defended = after.fork();
+                defended.push(from, to, *promotion).unwrap();
+                defended.mate_in_one().is_some()
+            }));
+
+        assert_eq!(Board::default().forced_mate(2), None);
+        assert_eq!(board.forced_mate(0), None);
+    }
+}
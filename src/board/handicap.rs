@@ -0,0 +1,167 @@
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use super::{Board, Coord};
+use crate::piece::Color;
+
+/// Classical material odds, given by one side at the start of the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "python", pyclass)]
+pub enum Handicap {
+    /// Without the queen
+    Queen,
+    /// Without the queen's rook (and its castling right)
+    Rook,
+    /// Without the queen's knight
+    Knight,
+    /// Without the f pawn, and the opponent moves first
+    PawnAndMove,
+}
+
+impl Handicap {
+    /// Name of the odds as written in game headers, e.g. "Queen odds"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Queen => "Queen odds",
+            Self::Rook => "Rook odds",
+            Self::Knight => "Knight odds",
+            Self::PawnAndMove => "Pawn and move odds",
+        }
+    }
+
+    /// PGN tag pairs of a game where `color` gives these odds: the starting
+    /// position (`SetUp` and `FEN`) and the odds with who gives them
+    pub fn pgn_headers(&self, color: Color) -> Vec<(String, String)> {
+        let giver = match color {
+            Color::White => "White",
+            Color::Black => "Black",
+        };
+
+        vec![
+            ("SetUp".to_string(), "1".to_string()),
+            (
+                "FEN".to_string(),
+                Board::with_handicap(color, *self).to_fen(),
+            ),
+            ("Odds".to_string(), format!("{} by {}", self.name(), giver)),
+        ]
+    }
+
+    /// Cell (on the initial board) of the piece removed for `color`
+    fn removed_cell(&self, color: Color) -> Coord {
+        let (back_row, pawn_row) = match color {
+            Color::White => (7, 6),
+            Color::Black => (0, 1),
+        };
+
+        let (row, col) = match self {
+            Self::Queen => (back_row, 3),
+            Self::Rook => (back_row, 0),
+            Self::Knight => (back_row, 1),
+            Self::PawnAndMove => (pawn_row, 5),
+        };
+
+        Coord { row, col }
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Handicap {
+    #[pyo3(name = "name")]
+    fn py_name(&self) -> &'static str {
+        self.name()
+    }
+
+    #[pyo3(name = "pgn_headers")]
+    fn py_pgn_headers(&self, color: Color) -> Vec<(String, String)> {
+        self.pgn_headers(color)
+    }
+}
+
+impl Board {
+    /// Initial position where `color` gives the `handicap` odds to its opponent
+    pub fn with_handicap(color: Color, handicap: Handicap) -> Self {
+        let mut board = Self::default();
+        let cell = handicap.removed_cell(color);
+
+        board.remove_piece(&cell);
+
+        if let Some(rights) = board.info.castling.get_mut(&color) {
+            rights.retain(|right| right.rook != cell);
+        }
+
+        if handicap == Handicap::PawnAndMove {
+            board.info.turn = color.opposite();
+        }
+
+        board
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::pgn::parse_games;
+    use crate::PieceType;
+
+    #[test]
+    fn test_queen_odds() {
+        let board = Board::with_handicap(Color::White, Handicap::Queen);
+
+        assert_eq!(board.get_all_pieces(&Color::White).len(), 15);
+        assert_eq!(board.get_all_pieces(&Color::Black).len(), 16);
+        assert!(!board
+            .get_all_pieces(&Color::White)
+            .iter()
+            .any(|p| p.piece == PieceType::Queen));
+        assert_eq!(board.info.turn, Color::White);
+    }
+
+    #[test]
+    fn test_rook_odds_castling() {
+        let board = Board::with_handicap(Color::Black, Handicap::Rook);
+
+        assert!(board
+            .get_piece(&Coord { row: 0, col: 0 })
+            .unwrap()
+            .is_none());
+
+        let rights = board.info.castling.get(&Color::Black).unwrap();
+        assert_eq!(rights.len(), 1);
+        assert_eq!(rights[0].rook, Coord { row: 0, col: 7 });
+        assert_eq!(board.info.castling.get(&Color::White).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_pawn_and_move() {
+        let board = Board::with_handicap(Color::White, Handicap::PawnAndMove);
+
+        assert!(board
+            .get_piece(&Coord { row: 6, col: 5 })
+            .unwrap()
+            .is_none());
+        assert_eq!(board.info.turn, Color::Black);
+    }
+
+    #[test]
+    fn test_pgn_headers() {
+        let headers = Handicap::PawnAndMove.pgn_headers(Color::White);
+        let pgn = headers
+            .iter()
+            .map(|(name, value)| format!("[{} \"{}\"]\n", name, value))
+            .collect::<String>()
+            + "\n1... e5 *\n";
+
+        let game = &parse_games(&pgn)[0];
+        assert_eq!(game.header("SetUp"), Some("1"));
+        assert_eq!(game.header("Odds"), Some("Pawn and move odds by White"));
+        assert_eq!(
+            game.header("FEN"),
+            Some("rnbqkbnr/pppppppp/8/8/8/8/PPPPP1PP/RNBQKBNR b KQkq - 0 1")
+        );
+
+        let board = Board::from_fen(game.header("FEN").unwrap()).unwrap();
+        assert!(board == Board::with_handicap(Color::White, Handicap::PawnAndMove));
+    }
+}
@@ -0,0 +1,116 @@
+use super::Board;
+use crate::piece::{Color, Piece, PieceType};
+
+impl Board {
+    /// True if `color` can't checkmate by any series of legal moves, even with
+    /// the help of the other side (FIDE): a bare king, a king and a knight
+    /// against a bare king, or a king and bishops of a single cell color
+    /// against a king and bishops of that color at most.
+    pub fn insufficient_material_for(&self, color: Color) -> bool {
+        let cell_color = |piece: &Piece| (piece.coord.row + piece.coord.col) % 2;
+        let not_king = |piece: &&Piece| piece.piece != PieceType::King;
+
+        let pieces: Vec<&Piece> = self
+            .get_all_pieces(&color)
+            .into_iter()
+            .filter(not_king)
+            .collect();
+        let others: Vec<&Piece> = self
+            .get_all_pieces(&color.opposite())
+            .into_iter()
+            .filter(not_king)
+            .collect();
+
+        match pieces.as_slice() {
+            [] => true,
+            // the other side has nothing to block the escape cells of its king
+            [knight] if knight.piece == PieceType::Knight => others.is_empty(),
+            [bishop, ..] if pieces.iter().all(|p| p.piece == PieceType::Bishop) => {
+                // blockers on the other cell color are never attacked
+                let color = cell_color(bishop);
+                pieces
+                    .iter()
+                    .chain(others.iter())
+                    .all(|p| p.piece == PieceType::Bishop && cell_color(p) == color)
+            }
+            _ => false,
+        }
+    }
+
+    /// Neither side can checkmate: the game is drawn
+    pub fn is_insufficient_material(&self) -> bool {
+        self.insufficient_material_for(Color::White) && self.insufficient_material_for(Color::Black)
+    }
+
+    /// Winner when `flagged` runs out of time: the other side, unless it
+    /// can't checkmate, which makes the game a draw (None)
+    pub fn timeout_winner(&self, flagged: Color) -> Option<Color> {
+        let winner = flagged.opposite();
+        match self.insufficient_material_for(winner) {
+            true => None,
+            false => Some(winner),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insufficient(fen: &str) -> (bool, bool) {
+        let board = Board::from_fen(fen).unwrap();
+        (
+            board.insufficient_material_for(Color::White),
+            board.insufficient_material_for(Color::Black),
+        )
+    }
+
+    #[test]
+    fn test_insufficient_material() {
+        assert_eq!(insufficient("4k3/8/8/8/8/8/8/4K3 w - - 0 1"), (true, true));
+        assert_eq!(insufficient("4k3/8/8/8/8/8/8/4KN2 w - - 0 1"), (true, true));
+        assert_eq!(
+            insufficient("4k3/8/8/8/8/8/8/3NKN2 w - - 0 1"),
+            (false, true)
+        );
+        // the knight can mate if the rook blocks the king
+        assert_eq!(
+            insufficient("4k2r/8/8/8/8/8/8/4KN2 w - - 0 1"),
+            (false, false)
+        );
+        assert_eq!(
+            insufficient("4k3/8/8/8/8/8/8/4KQ2 w - - 0 1"),
+            (false, true)
+        );
+        assert_eq!(
+            insufficient("4k3/8/8/8/8/8/8/4KP2 w - - 0 1"),
+            (false, true)
+        );
+
+        // bishops on light cells (f1, d3) and a light-cell bishop on d5
+        assert_eq!(
+            insufficient("4k3/8/8/3b4/8/3B4/8/4KB2 w - - 0 1"),
+            (true, true)
+        );
+        // a dark-cell bishop can block
+        assert_eq!(
+            insufficient("4k3/8/8/4b3/8/8/8/4KB2 w - - 0 1"),
+            (false, false)
+        );
+        assert_eq!(
+            insufficient("4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1"),
+            (false, true)
+        );
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4KN2 w - - 0 1").unwrap();
+        assert!(board.is_insufficient_material());
+        assert!(!Board::default().is_insufficient_material());
+    }
+
+    #[test]
+    fn test_timeout_winner() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4KQ2 w - - 0 1").unwrap();
+        assert_eq!(board.timeout_winner(Color::Black), Some(Color::White));
+        assert_eq!(board.timeout_winner(Color::White), None);
+    }
+}
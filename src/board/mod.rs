@@ -1,17 +1,60 @@
+mod annotations;
+mod attacks;
+mod bitboard;
 mod board;
+mod canonical;
+mod consistency;
+mod descriptor;
+mod effects;
+mod encoding;
+mod forcing;
 mod board_info;
+mod handicap;
+mod material;
+mod motifs;
+mod move_cache;
+mod movegen;
+mod observers;
+mod pins;
+mod push;
+mod svg;
 
+pub use annotations::{Annotations, ANNOTATION_COLOR};
+pub use attacks::{king_attacks, knight_attacks, pawn_attacks};
+pub use bitboard::Bitboard;
 pub use board::Board;
 pub use board_info::BoardInfo;
 pub use board_info::CastlingRights;
+pub use canonical::{dedup_positions, duplicate_report, DuplicateReport};
+pub use descriptor::PositionDescriptor;
+pub use effects::MoveEffect;
+pub use encoding::FullMove;
+pub use forcing::MAX_MATE_DEPTH;
+pub use handicap::Handicap;
+pub use motifs::Motif;
+pub use move_cache::CacheStats;
+pub use movegen::{MoveCoords, MoveGen};
+pub use observers::Observer;
+pub use pins::CheckInfo;
+pub use push::{MoveError, MoveRecord};
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 use std::ops::Add;
 pub trait HasCoordinates {
     fn get_coordinates(&self) -> Coord;
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-#[pyclass]
+/// A cell of the board, as the rows and columns of its FEN placement: row 0
+/// is the top row, the last rank (rank 8 in chess), where Black starts, and
+/// the last row is rank 1, where White starts. Column 0 is the a-file. White
+/// pawns move towards row 0.
+///
+/// Use `from_rank_file` / `to_rank_file` to work with ranks instead of rows.
+///
+/// Coords are ordered by row and then column: top to bottom and left to
+/// right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "python", pyclass)]
 pub struct Coord {
     pub row: i32,
     pub col: i32,
@@ -30,6 +73,54 @@ impl Coord {
             col: self.col + rhs.col,
         }
     }
+
+    /// Cell of a rank and file counted from 0 and from White's side (rank 0 is
+    /// rank 1 in chess, file 0 the a-file), on a board with `rows` rows
+    pub fn from_rank_file(rank: i32, file: i32, rows: u32) -> Self {
+        Self {
+            row: rows as i32 - 1 - rank,
+            col: file,
+        }
+    }
+
+    /// Inverse of `from_rank_file`: `(rank, file)` counted from 0
+    pub fn to_rank_file(&self, rows: u32) -> (i32, i32) {
+        (rows as i32 - 1 - self.row, self.col)
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Coord {
+    #[new]
+    fn py_new(row: i32, col: i32) -> Self {
+        Self { row, col }
+    }
+
+    #[getter(row)]
+    fn py_row(&self) -> i32 {
+        self.row
+    }
+
+    #[getter(col)]
+    fn py_col(&self) -> i32 {
+        self.col
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_rank_file", signature = (rank, file, rows = 8))]
+    fn py_from_rank_file(rank: i32, file: i32, rows: u32) -> Self {
+        Self::from_rank_file(rank, file, rows)
+    }
+
+    #[pyo3(name = "to_rank_file", signature = (rows = 8))]
+    fn py_to_rank_file(&self, rows: u32) -> (i32, i32) {
+        self.to_rank_file(rows)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Coord(row={}, col={})", self.row, self.col)
+    }
 }
 
 impl Add for Coord {
@@ -0,0 +1,283 @@
+use super::pins::DIRECTIONS;
+use super::{Board, Coord, MoveError};
+use crate::moves::util::{direction_iter, ray_between};
+use crate::moves::Direction;
+use crate::piece::{Color, PieceType};
+
+/// A tactical pattern of one side's pieces, see `Board::motifs`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Motif {
+    /// `attacker` attacks two or more enemy pieces that are the king, worth
+    /// more than it or undefended
+    Fork {
+        attacker: Coord,
+        targets: Vec<Coord>,
+    },
+    /// `pinned` can't leave the line of `attacker` without exposing the more
+    /// valuable `behind`
+    Pin {
+        attacker: Coord,
+        pinned: Coord,
+        behind: Coord,
+    },
+    /// `attacker` attacks `front`, which is more valuable than `behind` and
+    /// exposes it when it moves away
+    Skewer {
+        attacker: Coord,
+        front: Coord,
+        behind: Coord,
+    },
+    /// Moving a piece away from `moved_from` opened the line of `attacker` to
+    /// `target`
+    DiscoveredAttack {
+        attacker: Coord,
+        moved_from: Coord,
+        target: Coord,
+    },
+}
+
+impl Motif {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Fork { .. } => "fork",
+            Self::Pin { .. } => "pin",
+            Self::Skewer { .. } => "skewer",
+            Self::DiscoveredAttack { .. } => "discovered_attack",
+        }
+    }
+
+    /// The cells of the motif, attacker first, in the order of its fields
+    pub fn cells(&self) -> Vec<Coord> {
+        match self {
+            Self::Fork { attacker, targets } => std::iter::once(*attacker)
+                .chain(targets.iter().copied())
+                .collect(),
+            Self::Pin {
+                attacker,
+                pinned,
+                behind,
+            } => vec![*attacker, *pinned, *behind],
+            Self::Skewer {
+                attacker,
+                front,
+                behind,
+            } => vec![*attacker, *front, *behind],
+            Self::DiscoveredAttack {
+                attacker,
+                moved_from,
+                target,
+            } => vec![*attacker, *moved_from, *target],
+        }
+    }
+}
+
+/// Material value used to compare pieces, kings above everything
+fn value(piece: PieceType) -> u32 {
+    match piece {
+        PieceType::Pawn => 1,
+        PieceType::Knight | PieceType::Bishop | PieceType::Custom(_) => 3,
+        PieceType::Rook => 5,
+        PieceType::Queen => 9,
+        PieceType::King => 1000,
+    }
+}
+
+impl Board {
+    /// Forks, pins and skewers of the `color` pieces against the other side
+    pub fn motifs(&self, color: Color) -> Vec<Motif> {
+        let mut motifs = self.forks(color);
+        motifs.append(&mut self.line_motifs(color));
+        motifs
+    }
+
+    /// Motifs of the moving side that the move creates: the `motifs` after it
+    /// that were not there before, and discovered attacks
+    pub fn motifs_after(
+        &self,
+        from: &Coord,
+        to: &Coord,
+        promotion: Option<PieceType>,
+    ) -> Result<Vec<Motif>, MoveError> {
+        let color = self.info.turn;
+        let mut after = self.fork();
+        after.push(from, to, promotion)?;
+
+        let before = self.motifs(color);
+        let mut motifs: Vec<Motif> = after
+            .motifs(color)
+            .into_iter()
+            .filter(|motif| !before.contains(motif))
+            .collect();
+
+        for target in after.get_all_pieces(&color.opposite()) {
+            let attacked_before = self.attackers(&target.coord, color);
+            for attacker in after.attackers(&target.coord, color) {
+                if attacker != *to
+                    && !attacked_before.contains(&attacker)
+                    && ray_between(&attacker, &target.coord).contains(from)
+                {
+                    motifs.push(Motif::DiscoveredAttack {
+                        attacker,
+                        moved_from: *from,
+                        target: target.coord,
+                    });
+                }
+            }
+        }
+
+        Ok(motifs)
+    }
+
+    fn forks(&self, color: Color) -> Vec<Motif> {
+        let enemy = color.opposite();
+        let mut forks = vec![];
+
+        for attacker in self.get_all_pieces(&color) {
+            let targets: Vec<Coord> = self
+                .get_all_pieces(&enemy)
+                .iter()
+                .filter(|target| {
+                    target.piece == PieceType::King
+                        || value(target.piece) > value(attacker.piece)
+                        || !self.is_square_attacked(&target.coord, enemy)
+                })
+                .filter(|target| {
+                    self.attackers(&target.coord, color)
+                        .contains(&attacker.coord)
+                })
+                .map(|target| target.coord)
+                .collect();
+
+            if targets.len() >= 2 {
+                forks.push(Motif::Fork {
+                    attacker: attacker.coord,
+                    targets,
+                });
+            }
+        }
+
+        forks
+    }
+
+    /// Pins and skewers: rays of the `color` sliders that go through an enemy
+    /// piece to another enemy piece
+    fn line_motifs(&self, color: Color) -> Vec<Motif> {
+        let mut motifs = vec![];
+
+        for attacker in self.get_all_pieces(&color) {
+            for direction in DIRECTIONS {
+                let orthogonal = matches!(
+                    direction,
+                    Direction::North | Direction::South | Direction::East | Direction::West
+                );
+                let slides = match attacker.piece {
+                    PieceType::Queen => true,
+                    PieceType::Rook => orthogonal,
+                    PieceType::Bishop => !orthogonal,
+                    _ => false,
+                };
+                if !slides {
+                    continue;
+                }
+
+                let mut front = None;
+
+                for current in direction_iter(&attacker.coord, &direction) {
+                    let cell = match self.get_piece(&current) {
+                        Ok(cell) => cell,
+                        Err(_) => break,
+                    };
+                    if let Some(piece) = cell {
+                        if piece.color == color {
+                            break;
+                        }
+
+                        match front {
+                            None => front = Some(piece),
+                            Some(front) => {
+                                let (front_value, behind_value) =
+                                    (value(front.piece), value(piece.piece));
+                                if behind_value > front_value {
+                                    motifs.push(Motif::Pin {
+                                        attacker: attacker.coord,
+                                        pinned: front.coord,
+                                        behind: piece.coord,
+                                    });
+                                } else if front_value > behind_value {
+                                    motifs.push(Motif::Skewer {
+                                        attacker: attacker.coord,
+                                        front: front.coord,
+                                        behind: piece.coord,
+                                    });
+                                }
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        motifs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(cell: &str) -> Coord {
+        Board::default().notation().cell_from_str(cell).unwrap()
+    }
+
+    #[test]
+    fn test_fork() {
+        // the e5 knight forks the king and the rook, not the defended pawn
+        let board = Board::from_fen("8/3k1r2/2p5/4N3/8/8/8/4K3 b - - 0 1").unwrap();
+        assert_eq!(
+            board.motifs(Color::White),
+            [Motif::Fork {
+                attacker: coord("e5"),
+                targets: vec![coord("d7"), coord("f7")],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_pin_and_skewer() {
+        // b5 bishop pins the c6 knight to the e8 king, the h1 rook skewers
+        // the h5 queen and the h8 rook
+        let board = Board::from_fen("4k2r/8/2n5/1B5q/8/8/8/4K2R w - - 0 1").unwrap();
+        let motifs = board.motifs(Color::White);
+        assert!(motifs.contains(&Motif::Pin {
+            attacker: coord("b5"),
+            pinned: coord("c6"),
+            behind: coord("e8"),
+        }));
+        assert!(motifs.contains(&Motif::Skewer {
+            attacker: coord("h1"),
+            front: coord("h5"),
+            behind: coord("h8"),
+        }));
+        assert_eq!(motifs.len(), 2);
+    }
+
+    #[test]
+    fn test_motifs_after() {
+        // Nd4-f5 uncovers the d1 rook on the d8 queen and forks e7 and g7
+        let board = Board::from_fen("3q2k1/4r1r1/8/8/3N4/8/8/1K1R4 w - - 0 1").unwrap();
+        let motifs = board
+            .motifs_after(&coord("d4"), &coord("f5"), None)
+            .unwrap();
+
+        assert_eq!(
+            motifs.iter().map(|m| m.name()).collect::<Vec<_>>(),
+            ["fork", "discovered_attack"]
+        );
+        assert_eq!(motifs[1].cells(), [coord("d1"), coord("d4"), coord("d8")]);
+
+        assert!(board
+            .motifs_after(&coord("d4"), &coord("d5"), None)
+            .is_err());
+    }
+}
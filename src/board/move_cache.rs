@@ -0,0 +1,119 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use super::{Board, Coord};
+
+/// Entries kept by each board's legal move cache
+const CAPACITY: usize = 256;
+
+/// Usage of the legal move cache of a board, see `Board::cached_legal_moves`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Entries in the cache
+    pub len: usize,
+}
+
+/// Least recently used entries last
+#[derive(Default)]
+struct Lru {
+    entries: VecDeque<((u64, Coord), Vec<Coord>)>,
+    hits: u64,
+    misses: u64,
+}
+
+/// Legal moves by position hash and origin cell. Like observers, clones and
+/// forks start with an empty cache.
+#[derive(Default)]
+pub(super) struct MoveCache(Mutex<Lru>);
+
+impl Clone for MoveCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl Board {
+    /// `legal_moves` through a small LRU cache keyed by `position_hash` and
+    /// `from`, for repeated queries on the same position (e.g. a GUI asking
+    /// for the moves of the hovered cell).
+    ///
+    /// The hash only covers the position, so the cache must be cleared if
+    /// the rules change (`info.double_step_ranks`, move effects...).
+    pub fn cached_legal_moves(&self, from: &Coord) -> Vec<Coord> {
+        let key = (self.position_hash(), *from);
+        let mut lru = self.move_cache.0.lock().unwrap();
+
+        if let Some(index) = lru.entries.iter().position(|(k, _)| *k == key) {
+            lru.hits += 1;
+            let entry = lru.entries.remove(index).expect("The index was found");
+            let moves = entry.1.clone();
+            lru.entries.push_front(entry);
+            return moves;
+        }
+
+        lru.misses += 1;
+        let moves = self.legal_moves(from);
+        lru.entries.push_front((key, moves.clone()));
+        lru.entries.truncate(CAPACITY);
+        moves
+    }
+
+    pub fn cache_stats(&self) -> CacheStats {
+        let lru = self.move_cache.0.lock().unwrap();
+        CacheStats {
+            hits: lru.hits,
+            misses: lru.misses,
+            len: lru.entries.len(),
+        }
+    }
+
+    /// Empties the legal move cache and resets its stats
+    pub fn clear_cache(&self) {
+        *self.move_cache.0.lock().unwrap() = Lru::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_cache() {
+        let mut board = Board::default();
+        let e2 = Coord::from_rank_file(1, 4, 8);
+
+        assert_eq!(board.cached_legal_moves(&e2), board.legal_moves(&e2));
+        assert_eq!(board.cached_legal_moves(&e2).len(), 2);
+        assert_eq!(
+            board.cache_stats(),
+            CacheStats {
+                hits: 1,
+                misses: 1,
+                len: 1
+            }
+        );
+
+        // another position is another entry
+        board.push_uci("d2d4").unwrap();
+        board.push_uci("e7e5").unwrap();
+        assert_eq!(board.cached_legal_moves(&e2).len(), 2);
+        assert_eq!(board.cache_stats().len, 2);
+        assert_eq!(board.fork().cache_stats(), CacheStats::default());
+
+        board.clear_cache();
+        assert_eq!(board.cache_stats(), CacheStats::default());
+
+        // 5 positions of 64 cells, the oldest entries are dropped
+        for uci in ["g1f3", "b8c6", "b1c3", "g8f6", "c1f4"] {
+            board.push_uci(uci).unwrap();
+            for row in 0..8 {
+                for col in 0..8 {
+                    board.cached_legal_moves(&Coord { row, col });
+                }
+            }
+        }
+        assert_eq!(board.cache_stats().len, CAPACITY);
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::btree_set;
+
+use super::{Board, Coord};
+use crate::piece::Piece;
+
+/// A move as its `(from, to)` cells
+pub type MoveCoords = (Coord, Coord);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Hint,
+    Captures,
+    Killers,
+    Quiets,
+    Done,
+}
+
+/// Staged move generator for the side to move.
+///
+/// Moves are yielded lazily in the order a search wants to try them:
+/// 1. The hint move (e.g. from a transposition table), if it is valid.
+/// 2. Captures, generated one piece at a time.
+/// 3. Killer moves, if they are valid quiet moves.
+/// 4. The remaining quiet moves.
+///
+/// A move is never yielded twice, and stopping the iterator early skips the
+/// generation of the pieces that were not reached yet. Pieces are found by
+/// scanning the board and their targets are read straight from
+/// `Piece::get_moves`, so the only buffer kept is the quiet moves.
+pub struct MoveGen<'a> {
+    board: &'a Board,
+    stage: Stage,
+    hint: Option<MoveCoords>,
+    killers: std::slice::Iter<'a, MoveCoords>,
+    /// Next cell to look for a piece of the side to move, in row-major order
+    next_cell: usize,
+    /// Piece being generated and its targets not yielded yet
+    current: Option<(Coord, btree_set::IntoIter<Coord>)>,
+    /// Quiet moves found during the capture stage
+    quiets: Vec<MoveCoords>,
+}
+
+impl<'a> MoveGen<'a> {
+    pub fn new(board: &'a Board, hint: Option<MoveCoords>, killers: &'a [MoveCoords]) -> Self {
+        Self {
+            board,
+            stage: Stage::Hint,
+            hint,
+            killers: killers.iter(),
+            next_cell: 0,
+            current: None,
+            quiets: vec![],
+        }
+    }
+
+    fn next_piece(&mut self) -> Option<&'a Piece> {
+        let cols = self.board.get_cols() as usize;
+        let cells = self.board.get_rows() as usize * cols;
+
+        while self.next_cell < cells {
+            let coord = Coord {
+                row: (self.next_cell / cols) as i32,
+                col: (self.next_cell % cols) as i32,
+            };
+            self.next_cell += 1;
+
+            if let Ok(Some(piece)) = self.board.get_piece(&coord) {
+                if piece.color == self.board.info.turn {
+                    return Some(piece);
+                }
+            }
+        }
+
+        None
+    }
+
+    fn next_capture(&mut self) -> Option<MoveCoords> {
+        loop {
+            if let Some((from, targets)) = self.current.as_mut() {
+                for to in targets.by_ref() {
+                    let m = (*from, to);
+                    if self.hint == Some(m) {
+                        continue;
+                    }
+                    if matches!(self.board.get_piece(&to), Ok(Some(_))) {
+                        return Some(m);
+                    }
+                    self.quiets.push(m);
+                }
+            }
+
+            let piece = self.next_piece()?;
+            self.current = Some((piece.coord, piece.get_moves(self.board).into_iter()));
+        }
+    }
+
+    fn next_killer(&mut self) -> Option<MoveCoords> {
+        for m in self.killers.by_ref() {
+            // Killers come from sibling nodes, so they are only yielded if
+            // they are one of the quiet moves of this position
+            if let Some(index) = self.quiets.iter().position(|quiet| quiet == m) {
+                self.quiets.remove(index);
+                return Some(*m);
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a> Iterator for MoveGen<'a> {
+    type Item = MoveCoords;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.stage {
+                Stage::Hint => {
+                    self.stage = Stage::Captures;
+                    if let Some((from, to)) = self.hint {
+                        if self.board.can_move(&from, &to, false) {
+                            return Some((from, to));
+                        }
+                        self.hint = None;
+                    }
+                }
+                Stage::Captures => match self.next_capture() {
+                    Some(m) => return Some(m),
+                    None => self.stage = Stage::Killers,
+                },
+                Stage::Killers => match self.next_killer() {
+                    Some(m) => return Some(m),
+                    None => {
+                        self.quiets.reverse();
+                        self.stage = Stage::Quiets;
+                    }
+                },
+                Stage::Quiets => match self.quiets.pop() {
+                    Some(m) => return Some(m),
+                    None => self.stage = Stage::Done,
+                },
+                Stage::Done => return None,
+            }
+        }
+    }
+}
+
+impl Board {
+    /// Staged, lazy generation of the moves of the side to move (see `MoveGen`)
+    pub fn move_gen<'a>(
+        &'a self,
+        hint: Option<MoveCoords>,
+        killers: &'a [MoveCoords],
+    ) -> MoveGen<'a> {
+        MoveGen::new(self, hint, killers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn all_moves(board: &Board) -> HashSet<MoveCoords> {
+        board
+            .get_all_pieces(&board.info.turn)
+            .iter()
+            .flat_map(|piece| {
+                board
+                    .get_moves(&piece.coord, false)
+                    .into_iter()
+                    .map(|to| (piece.coord, to))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_same_moves() {
+        let board =
+            Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/3PP3/5N2/PPP2PPP/RNBQKB1R b KQkq - 0 3")
+                .unwrap();
+
+        let generated: Vec<MoveCoords> = board.move_gen(None, &[]).collect();
+        let unique: HashSet<MoveCoords> = generated.iter().copied().collect();
+
+        assert_eq!(generated.len(), unique.len());
+        assert_eq!(unique, all_moves(&board));
+    }
+
+    #[test]
+    fn test_stage_order() {
+        // e4 pawn can capture d5
+        let board = Board::from_fen("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2")
+            .unwrap();
+
+        let e2 = Coord { row: 6, col: 4 };
+        let g1 = Coord { row: 7, col: 6 };
+        let f3 = Coord { row: 5, col: 5 };
+        let capture = (Coord { row: 4, col: 4 }, Coord { row: 3, col: 3 });
+        let hint = (g1, f3);
+        // not valid: e2 is empty
+        let killers = [
+            (e2, Coord { row: 5, col: 4 }),
+            (Coord { row: 7, col: 3 }, Coord { row: 4, col: 6 }),
+        ];
+
+        let moves: Vec<MoveCoords> = board.move_gen(Some(hint), &killers).collect();
+
+        assert_eq!(moves[0], hint);
+        assert_eq!(moves[1], capture);
+        // d1-g4 queen killer right after the captures
+        assert_eq!(moves[2], killers[1]);
+        assert_eq!(moves.iter().filter(|m| **m == hint).count(), 1);
+        assert_eq!(moves.len(), all_moves(&board).len());
+    }
+
+    #[test]
+    fn test_invalid_hint() {
+        let board = Board::default();
+        let hint = (Coord { row: 7, col: 0 }, Coord { row: 5, col: 0 });
+
+        let moves: Vec<MoveCoords> = board.move_gen(Some(hint), &[]).collect();
+        assert!(!moves.contains(&hint));
+        assert_eq!(moves.len(), 20);
+    }
+}
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use super::{Board, MoveRecord};
+
+/// Called after every move played on a board, with the move and the board
+/// after it
+pub type Observer = Arc<dyn Fn(&MoveRecord, &Board) + Send + Sync>;
+
+/// Observers belong to the board they were added to: clones and forks start
+/// without them, so copies made for search or analysis don't notify.
+#[derive(Default)]
+pub(super) struct Observers(Vec<Observer>);
+
+impl Clone for Observers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl Board {
+    /// Registers a function called after every `push` (or `push_uci`,
+    /// `push_san`, `make_null_move`...) on this board. Taking moves back
+    /// with `pop` is not notified.
+    pub fn add_observer<F>(&mut self, observer: F)
+    where
+        F: Fn(&MoveRecord, &Board) + Send + Sync + 'static,
+    {
+        self.observers.0.push(Arc::new(observer));
+    }
+
+    pub fn clear_observers(&mut self) {
+        self.observers.0.clear();
+    }
+
+    pub(super) fn notify(&self, record: &MoveRecord) {
+        for observer in &self.observers.0 {
+            observer(record, self);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piece::Color;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_observers() {
+        let played = Arc::new(Mutex::new(vec![]));
+        let mut board = Board::default();
+
+        let log = played.clone();
+        board.add_observer(move |record, board| {
+            log.lock()
+                .unwrap()
+                .push((record.uci.clone(), board.is_in_check(Color::Black)));
+        });
+
+        for uci in ["e2e4", "f7f6", "d1h5"] {
+            board.push_uci(uci).unwrap();
+        }
+        assert!(board.push_uci("a1a8").is_err());
+        board.pop();
+        board.make_null_move();
+
+        // copies don't notify
+        board.fork().push_uci("e7e5").unwrap();
+        board.clone().push_uci("e7e5").unwrap();
+
+        board.clear_observers();
+        board.push_uci("e7e5").unwrap();
+
+        assert_eq!(
+            *played.lock().unwrap(),
+            [
+                ("e2e4".to_string(), false),
+                ("f7f6".to_string(), false),
+                ("d1h5".to_string(), true),
+                ("0000".to_string(), false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use std::sync::Mutex;
+
+use super::{Bitboard, Board, Coord};
+use crate::moves::{util::direction_iter, Direction};
+use crate::piece::{Color, PieceType};
+
+pub(super) const DIRECTIONS: [Direction; 8] = [
+    Direction::North,
+    Direction::South,
+    Direction::East,
+    Direction::West,
+    Direction::NorthEast,
+    Direction::NorthWest,
+    Direction::SouthEast,
+    Direction::SouthWest,
+];
+
+/// Checks and pins of the side to move, computed once with `Board::check_info`
+/// and reused while filtering the moves of a position.
+///
+/// Bitboards only cover 8x8 boards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckInfo {
+    /// Side to move
+    pub color: Color,
+    /// Enemy pieces giving check
+    pub checkers: Bitboard,
+    /// Pieces of both colors that are the only piece between the king and an
+    /// enemy slider
+    pub blockers: Bitboard,
+    /// Pinned pieces of the side to move, with the cells they can still move
+    /// to: the ray from the king to the pinner, pinner included
+    pub pin_rays: Vec<(Coord, Bitboard)>,
+}
+
+impl CheckInfo {
+    pub fn is_check(&self) -> bool {
+        !self.checkers.is_empty()
+    }
+
+    /// Cells the piece in `coord` is limited to, if it is pinned (to all the
+    /// rays, if it is pinned to several royals)
+    pub fn pin_ray(&self, coord: &Coord) -> Option<Bitboard> {
+        self.pin_rays
+            .iter()
+            .filter(|(pinned, _)| pinned == coord)
+            .map(|(_, ray)| *ray)
+            .reduce(|a, b| a & b)
+    }
+}
+
+/// `CheckInfo` of the current position, computed on the first query and
+/// cleared whenever a piece moves, see `Board::invalidate_check_info`
+#[derive(Default)]
+pub(super) struct CheckCache(Mutex<Option<CheckInfo>>);
+
+impl Clone for CheckCache {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.0.lock().unwrap().clone()))
+    }
+}
+
+impl Board {
+    /// Checks and pins of the side to move. Cached until the next change of
+    /// the pieces or the turn, so legality checks of a position share it.
+    pub fn check_info(&self) -> CheckInfo {
+        let cached = self.check_cache.0.lock().unwrap().clone();
+        match cached {
+            // `info.turn` is public, so it may have changed under the cache
+            Some(info) if info.color == self.info.turn => info,
+            _ => {
+                // Not locked meanwhile: move generation may ask for it too
+                let info = self.compute_check_info();
+                *self.check_cache.0.lock().unwrap() = Some(info.clone());
+                info
+            }
+        }
+    }
+
+    pub(super) fn invalidate_check_info(&mut self) {
+        *self.check_cache.0.get_mut().unwrap() = None;
+    }
+
+    fn compute_check_info(&self) -> CheckInfo {
+        let color = self.info.turn;
+        let pins = self.king_pins(color);
+
+        CheckInfo {
+            color,
+            checkers: self.compute_checkers(),
+            blockers: pins.iter().map(|(blocker, _)| *blocker).collect(),
+            pin_rays: pins
+                .into_iter()
+                .filter(|(blocker, _)| {
+                    matches!(self.get_piece(blocker), Ok(Some(piece)) if piece.color == color)
+                })
+                .collect(),
+        }
+    }
+
+    /// Enemy pieces giving check to the side to move (to any of its royals)
+    pub fn checkers(&self) -> Bitboard {
+        self.check_info().checkers
+    }
+
+    fn compute_checkers(&self) -> Bitboard {
+        let color = self.info.turn;
+        self.royals(&color)
+            .iter()
+            .flat_map(|royal| self.attackers(royal, color.opposite()))
+            .collect()
+    }
+
+    /// Pieces (of any color) that are the only piece between a `color` royal
+    /// (the king) and an enemy rook, bishop or queen
+    pub fn blockers_for_king(&self, color: Color) -> Bitboard {
+        self.king_pins(color)
+            .iter()
+            .map(|(blocker, _)| *blocker)
+            .collect()
+    }
+
+    /// Pinned pieces of the side to move, see `CheckInfo::pin_rays`
+    pub fn pin_rays(&self) -> Vec<(Coord, Bitboard)> {
+        self.check_info().pin_rays
+    }
+
+    /// Walks from each royal in every direction, looking for a single piece
+    /// between the royal and an enemy slider
+    fn king_pins(&self, color: Color) -> Vec<(Coord, Bitboard)> {
+        self.royals(&color)
+            .iter()
+            .flat_map(|royal| self.royal_pins(royal, color))
+            .collect()
+    }
+
+    fn royal_pins(&self, royal: &Coord, color: Color) -> Vec<(Coord, Bitboard)> {
+        let mut pins = vec![];
+
+        for direction in DIRECTIONS {
+            let orthogonal = matches!(
+                direction,
+                Direction::North | Direction::South | Direction::East | Direction::West
+            );
+            let mut ray = Bitboard::EMPTY;
+            let mut blocker = None;
+
+            for current in direction_iter(royal, &direction) {
+                let cell = match self.get_piece(&current) {
+                    Ok(cell) => cell,
+                    Err(_) => break,
+                };
+                ray.insert(&current);
+
+                if let Some(piece) = cell {
+                    let slides = match piece.piece {
+                        PieceType::Queen => true,
+                        PieceType::Rook => orthogonal,
+                        PieceType::Bishop => !orthogonal,
+                        _ => false,
+                    };
+
+                    match blocker {
+                        None => blocker = Some(current),
+                        Some(blocker) => {
+                            if piece.color != color && slides {
+                                pins.push((blocker, ray));
+                            }
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        pins
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkers() {
+        let board = Board::from_fen("4k3/8/8/1B6/8/8/4r3/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            board.checkers(),
+            Bitboard::from_coords(&[Coord { row: 6, col: 4 }])
+        );
+
+        let board = Board::from_fen("4k3/8/8/1B6/8/8/4r3/4K3 b - - 0 1").unwrap();
+        let info = board.check_info();
+        assert!(info.is_check());
+        assert_eq!(
+            info.checkers,
+            Bitboard::from_coords(&[Coord { row: 3, col: 1 }])
+        );
+
+        assert!(!Board::default().check_info().is_check());
+    }
+
+    #[test]
+    fn test_pins() {
+        // d2 bishop pinned by the b4 bishop, e2 knight pinned by the e8 rook,
+        // the black f2 knight blocks the h4 bishop
+        let board = Board::from_fen("4r1k1/8/8/8/1b5b/8/3BNn2/4K3 w - - 0 1").unwrap();
+        let d2 = Coord { row: 6, col: 3 };
+        let e2 = Coord { row: 6, col: 4 };
+        let f2 = Coord { row: 6, col: 5 };
+
+        assert_eq!(
+            board.blockers_for_king(Color::White),
+            Bitboard::from_coords(&[d2, e2, f2])
+        );
+
+        let info = board.check_info();
+        assert_eq!(info.pin_rays.len(), 2);
+        assert_eq!(info.pin_ray(&f2), None);
+
+        let ray = info.pin_ray(&d2).unwrap();
+        assert_eq!(ray.len(), 3);
+        assert!(ray.contains(&Coord { row: 4, col: 1 }));
+
+        assert!(board.is_legal(&d2, &Coord { row: 5, col: 2 }));
+        assert!(!board.is_legal(&d2, &Coord { row: 5, col: 4 }));
+        assert!(!board.is_legal(&e2, &Coord { row: 4, col: 5 }));
+
+        // The same answers with the info computed once
+        for (from, to) in board.move_gen(None, &[]) {
+            assert_eq!(
+                board.is_legal_with(&info, &from, &to),
+                board.is_legal(&from, &to)
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_cache() {
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        assert!(!board.check_info().is_check());
+
+        board.push_uci("a1a8").unwrap();
+        assert!(board.check_info().is_check());
+        board.pop();
+        assert!(!board.check_info().is_check());
+
+        // Pieces and turn changed outside of moves
+        let rook = board.get_piece(&Coord { row: 7, col: 0 }).unwrap().cloned();
+        board.remove_piece(&Coord { row: 7, col: 0 });
+        let mut rook = rook.unwrap();
+        rook.coord = Coord { row: 6, col: 4 };
+        board.set_piece(rook);
+        board.info.turn = Color::Black;
+        assert!(board.check_info().is_check());
+
+        board.make_null_move();
+        assert!(!board.check_info().is_check());
+        board.unmake_null_move();
+        assert_eq!(board.check_info(), board.compute_check_info());
+        assert_eq!(board.fork().check_info(), board.check_info());
+    }
+}
@@ -0,0 +1,882 @@
+#[cfg(feature = "python")]
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use super::{Board, CheckInfo, Coord, FullMove};
+use crate::notation::{file_from_str, AlgebraicNotation};
+use crate::piece::{Color, Piece, PieceType};
+
+#[derive(Debug, PartialEq)]
+pub enum MoveError {
+    InvalidUci(String),
+    InvalidSan(String),
+    IllegalMove(String),
+    AmbiguousMove(String),
+    InvalidEncoding(String),
+}
+
+#[cfg(feature = "python")]
+impl std::convert::From<MoveError> for PyErr {
+    fn from(err: MoveError) -> PyErr {
+        PyValueError::new_err(format!("{:?}", err))
+    }
+}
+
+/// A move played on the board, as returned by `Board::push`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "python", pyclass)]
+pub struct MoveRecord {
+    pub from: Coord,
+    pub to: Coord,
+    pub color: Color,
+    pub piece: PieceType,
+    pub captured: Option<PieceType>,
+    pub promotion: Option<PieceType>,
+    pub castle: bool,
+    pub en_passant: bool,
+    /// The move checks the other side
+    pub gives_check: bool,
+    /// Some checking piece is not the moved one: it was uncovered by the move
+    pub discovered_check: bool,
+    /// Two or more pieces give check
+    pub double_check: bool,
+    /// The move in UCI notation, e.g. "e7e8q" ("0000" for a null move)
+    pub uci: String,
+}
+
+impl MoveRecord {
+    /// Null moves are recorded as the king moving to its own cell
+    pub fn is_null(&self) -> bool {
+        self.from == self.to
+    }
+
+    pub fn is_capture(&self) -> bool {
+        self.captured.is_some()
+    }
+}
+
+fn promotion_from_char(c: char) -> Option<PieceType> {
+    match c.to_ascii_lowercase() {
+        'q' => Some(PieceType::Queen),
+        'r' => Some(PieceType::Rook),
+        'b' => Some(PieceType::Bishop),
+        'n' => Some(PieceType::Knight),
+        _ => None,
+    }
+}
+
+fn promoted_piece(piece: PieceType, color: Color, coord: Coord) -> Option<Piece> {
+    match piece {
+        PieceType::Queen => Some(Piece::new_queen(color, coord)),
+        PieceType::Rook => Some(Piece::new_rook(color, coord)),
+        PieceType::Bishop => Some(Piece::new_bishop(color, coord)),
+        PieceType::Knight => Some(Piece::new_knight(color, coord)),
+        _ => None,
+    }
+}
+
+impl Board {
+    pub(super) fn notation(&self) -> AlgebraicNotation {
+        AlgebraicNotation {
+            rows: self.get_rows(),
+            cols: self.get_cols(),
+        }
+    }
+
+    pub(super) fn king_coord(&self, color: &Color) -> Option<Coord> {
+        self.get_all_pieces(color)
+            .iter()
+            .find(|piece| piece.piece == PieceType::King)
+            .map(|piece| piece.coord)
+    }
+
+    /// True if a royal piece (the king) of `color` is attacked
+    pub fn is_in_check(&self, color: Color) -> bool {
+        self.royals(&color)
+            .iter()
+            .any(|royal| self.is_square_attacked(royal, color.opposite()))
+    }
+
+    /// Checks if the side to move can play `from` -> `to` without leaving its
+    /// king in check
+    pub fn is_legal(&self, from: &Coord, to: &Coord) -> bool {
+        self.is_legal_with(&self.check_info(), from, to)
+    }
+
+    /// Same as `is_legal`, with the `check_info` of this position computed
+    /// once by the caller for all the moves it checks
+    pub fn is_legal_with(&self, info: &CheckInfo, from: &Coord, to: &Coord) -> bool {
+        let piece = match self.get_piece(from) {
+            Ok(Some(piece)) if self.can_move(from, to, false) => piece,
+            _ => return false,
+        };
+        let color = self.info.turn;
+
+        // Fast path with the pins, for the moves that can only expose the king
+        // along a pin ray. Custom pieces may pin in ways the rays don't know,
+        // and move effects may change any cell.
+        let en_passant = piece.piece == PieceType::Pawn
+            && from.col != to.col
+            && matches!(self.get_piece(to), Ok(None));
+        let custom_enemies = self
+            .get_all_pieces(&color.opposite())
+            .iter()
+            .any(|p| matches!(p.piece, PieceType::Custom(_)));
+
+        if self.is_standard_size()
+            && !piece.royal
+            && !en_passant
+            && !custom_enemies
+            && self.effects.is_empty()
+            && info.color == color
+            && !info.is_check()
+        {
+            return info.pin_ray(from).is_none_or(|ray| ray.contains(to));
+        }
+
+        let mut board = self.fork();
+        board.apply(from, to, Some(PieceType::Queen));
+        !board.is_in_check(color)
+    }
+
+    /// Moves of the piece in `from` that don't leave its king in check, sorted
+    /// like `get_moves`. Pieces of the side not to move get their moves as if
+    /// it was their turn.
+    pub fn legal_moves(&self, from: &Coord) -> Vec<Coord> {
+        let color = match self.get_piece(from) {
+            Ok(Some(piece)) => piece.color,
+            _ => return vec![],
+        };
+
+        if color != self.info.turn {
+            let mut board = self.fork();
+            board.info.turn = color;
+            board.info.en_passant = None;
+            return board.legal_moves(from);
+        }
+
+        self.legal_moves_with(&self.check_info(), from)
+    }
+
+    /// `legal_moves` of a piece of the side to move, see `is_legal_with`
+    pub(super) fn legal_moves_with(&self, info: &CheckInfo, from: &Coord) -> Vec<Coord> {
+        self.get_moves(from, false)
+            .into_iter()
+            .filter(|to| self.is_legal_with(info, from, to))
+            .collect()
+    }
+
+    /// Number of legal moves of the piece in each cell (0 if empty), indexed
+    /// `[row][col]`
+    pub fn mobility_map(&self) -> Vec<Vec<u32>> {
+        (0..self.get_rows() as i32)
+            .map(|row| {
+                (0..self.get_cols() as i32)
+                    .map(|col| self.legal_moves(&Coord { row, col }).len() as u32)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Plays `from` -> `to` for the side to move, updating the turn, castling
+    /// rights, en passant cell and move counters.
+    ///
+    /// `promotion` is required when a pawn reaches its promotion row, and not
+    /// allowed otherwise.
+    pub fn push(
+        &mut self,
+        from: &Coord,
+        to: &Coord,
+        promotion: Option<PieceType>,
+    ) -> Result<MoveRecord, MoveError> {
+        let uci = self.move_to_uci(from, to, promotion)?;
+
+        let piece = match self.get_piece(from) {
+            Ok(Some(piece)) => piece,
+            _ => {
+                return Err(MoveError::IllegalMove(format!(
+                    "No piece to move in {}",
+                    uci
+                )))
+            }
+        };
+
+        if !self.can_move(from, to, false) {
+            return Err(MoveError::IllegalMove(format!("{} can't be played", uci)));
+        }
+
+        let promotes = piece.piece == PieceType::Pawn && self.is_promotion_row(to.row, piece.color);
+        match (promotes, promotion) {
+            (true, None) => {
+                return Err(MoveError::IllegalMove(format!(
+                    "{} needs a promotion piece",
+                    uci
+                )))
+            }
+            (false, Some(_)) => {
+                return Err(MoveError::IllegalMove(format!(
+                    "{} is not a promotion",
+                    uci
+                )))
+            }
+            (true, Some(p)) if promoted_piece(p, piece.color, *to).is_none() => {
+                return Err(MoveError::IllegalMove(format!(
+                    "Can't promote to {:?} in {}",
+                    p, uci
+                )))
+            }
+            _ => (),
+        }
+
+        let color = self.info.turn;
+        let snapshot = self.snapshot();
+        let mut record = self.apply(from, to, promotion);
+
+        if self.is_in_check(color) {
+            self.restore(snapshot);
+            return Err(MoveError::IllegalMove(format!(
+                "{} leaves the king in check",
+                uci
+            )));
+        }
+
+        self.set_check_flags(&mut record);
+        if self.info.check_limit.is_some() && record.gives_check {
+            self.info.add_check(color);
+        }
+
+        record.uci = uci;
+        self.record_move(snapshot, record.clone());
+        self.notify(&record);
+        Ok(record)
+    }
+
+    /// Passes the turn: only the side to move changes and en passant is
+    /// cleared. Taken back with `unmake_null_move` (or `pop`).
+    pub fn make_null_move(&mut self) -> MoveRecord {
+        let color = self.info.turn;
+        let king = self.king_coord(&color).unwrap_or(Coord { row: 0, col: 0 });
+        let record = MoveRecord {
+            from: king,
+            to: king,
+            color,
+            piece: PieceType::King,
+            captured: None,
+            promotion: None,
+            castle: false,
+            en_passant: false,
+            gives_check: false,
+            discovered_check: false,
+            double_check: false,
+            uci: "0000".to_string(),
+        };
+
+        let snapshot = self.snapshot();
+        self.info.turn = color.opposite();
+        self.info.en_passant = None;
+        self.invalidate_check_info();
+        self.record_move(snapshot, record.clone());
+        self.notify(&record);
+
+        record
+    }
+
+    /// Takes back the last move if it is a null move. Returns false otherwise.
+    pub fn unmake_null_move(&mut self) -> bool {
+        match self.move_history().last() {
+            Some(record) if record.is_null() => self.pop().is_some(),
+            _ => false,
+        }
+    }
+
+    /// Plays a move written in UCI notation, e.g. "e2e4" or "e7e8q"
+    pub fn push_uci(&mut self, uci: &str) -> Result<MoveRecord, MoveError> {
+        let (from, to, promotion) = self.parse_uci(uci)?;
+        self.push(&from, &to, promotion)
+    }
+
+    pub(super) fn parse_uci(&self, uci: &str) -> Result<FullMove, MoveError> {
+        let invalid = || MoveError::InvalidUci(format!("Invalid UCI move {}", uci));
+
+        if !uci.is_ascii() || !(4..=5).contains(&uci.len()) {
+            return Err(invalid());
+        }
+
+        let notation = self.notation();
+        let from = notation.cell_from_str(&uci[0..2]).map_err(|_| invalid())?;
+        let to = notation.cell_from_str(&uci[2..4]).map_err(|_| invalid())?;
+        let promotion = match uci[4..].chars().next() {
+            Some(c) => Some(promotion_from_char(c).ok_or_else(invalid)?),
+            None => None,
+        };
+
+        Ok((from, to, promotion))
+    }
+
+    /// Plays a move written in Standard Algebraic Notation, e.g. "Nf3",
+    /// "exd5", "e8=Q+" or "O-O"
+    pub fn push_san(&mut self, san: &str) -> Result<MoveRecord, MoveError> {
+        let (from, to, promotion) = self.parse_san(san)?;
+        self.push(&from, &to, promotion)
+    }
+
+    fn parse_san(&self, san: &str) -> Result<(Coord, Coord, Option<PieceType>), MoveError> {
+        let invalid = || MoveError::InvalidSan(format!("Invalid SAN move {}", san));
+        let color = self.info.turn;
+
+        let clean = san.trim_end_matches(['+', '#', '!', '?']);
+        if !clean.is_ascii() || clean.is_empty() {
+            return Err(invalid());
+        }
+
+        // Castling
+        if matches!(clean, "O-O" | "0-0" | "O-O-O" | "0-0-0") {
+            let kingside = clean.len() == 3;
+            let king = self.king_coord(&color).ok_or_else(invalid)?;
+            let right = self
+                .info
+                .castling
+                .get(&color)
+                .and_then(|rights| {
+                    rights
+                        .iter()
+                        .find(|right| (right.rook.col > king.col) == kingside)
+                })
+                .ok_or_else(|| MoveError::IllegalMove(format!("{} can't be played", san)))?;
+            return Ok((king, right.new_king, None));
+        }
+
+        // Promotion: e8=Q or e8Q
+        let (body, promotion) = match clean.split_once('=') {
+            Some((body, piece)) if piece.len() == 1 => (body, piece.chars().next()),
+            Some(_) => return Err(invalid()),
+            None => match clean.chars().last() {
+                Some(c) if c.is_ascii_uppercase() && clean.len() > 2 => {
+                    (&clean[..clean.len() - 1], Some(c))
+                }
+                _ => (clean, None),
+            },
+        };
+        let promotion = match promotion {
+            Some(c) => Some(promotion_from_char(c).ok_or_else(invalid)?),
+            None => None,
+        };
+
+        // Destination: the longest suffix that is a cell, so multi-letter files
+        // and multi-digit ranks work on big boards
+        let notation = self.notation();
+        let (split, to) = (0..body.len())
+            .filter_map(|i| Some((i, notation.cell_from_str(body.get(i..)?).ok()?)))
+            .next()
+            .ok_or_else(invalid)?;
+
+        let mut prefix = body[..split].chars().peekable();
+        let piece = match prefix.peek() {
+            Some('K') => PieceType::King,
+            Some('Q') => PieceType::Queen,
+            Some('R') => PieceType::Rook,
+            Some('B') => PieceType::Bishop,
+            Some('N') => PieceType::Knight,
+            _ => PieceType::Pawn,
+        };
+        if piece != PieceType::Pawn {
+            prefix.next();
+        }
+
+        // Disambiguation: file, rank or both
+        let prefix: String = prefix.collect();
+        let prefix = prefix.strip_suffix('x').unwrap_or(&prefix);
+        let digits = prefix
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(prefix.len());
+        let (file, rank) = prefix.split_at(digits);
+
+        let file = match file {
+            "" => None,
+            file => Some(file_from_str(file).ok_or_else(invalid)? as i32),
+        };
+        let rank = match rank {
+            "" => None,
+            rank => {
+                let rank = rank.parse::<i32>().map_err(|_| invalid())?;
+                Some(self.get_rows() as i32 - rank)
+            }
+        };
+
+        let info = self.check_info();
+        let candidates: Vec<Coord> = self
+            .get_all_pieces(&color)
+            .iter()
+            .filter(|p| p.piece == piece)
+            .map(|p| p.coord)
+            .filter(|from| file.is_none_or(|col| from.col == col))
+            .filter(|from| rank.is_none_or(|row| from.row == row))
+            .filter(|from| self.is_legal_with(&info, from, &to))
+            .collect();
+
+        match candidates.len() {
+            1 => Ok((candidates[0], to, promotion)),
+            0 => Err(MoveError::IllegalMove(format!("{} can't be played", san))),
+            _ => Err(MoveError::AmbiguousMove(format!("{} is ambiguous", san))),
+        }
+    }
+
+    /// A move in UCI notation, e.g. "e7e8q"
+    pub fn move_to_uci(
+        &self,
+        from: &Coord,
+        to: &Coord,
+        promotion: Option<PieceType>,
+    ) -> Result<String, MoveError> {
+        let notation = self.notation();
+        let invalid = |_| MoveError::IllegalMove("Move out of the board".to_string());
+
+        let mut uci = notation.cell_to_str(from).map_err(invalid)?;
+        uci.push_str(&notation.cell_to_str(to).map_err(invalid)?);
+        if let Some(piece) = promotion {
+            uci.push(piece.to_char());
+        }
+
+        Ok(uci)
+    }
+
+    /// Moves the pieces, updates the board info and runs the move effects.
+    /// Does not validate the move.
+    fn apply(&mut self, from: &Coord, to: &Coord, promotion: Option<PieceType>) -> MoveRecord {
+        let piece = self
+            .get_piece(from)
+            .ok()
+            .flatten()
+            .cloned()
+            .expect("There should be a piece to move");
+        let color = piece.color;
+
+        let mut captured = self.get_piece(to).ok().flatten().map(|p| p.piece);
+
+        // En passant: the captured pawn is next to the origin cell
+        let en_passant = piece.piece == PieceType::Pawn
+            && captured.is_none()
+            && from.col != to.col
+            && self.info.en_passant == Some(*to);
+        if en_passant {
+            let pawn = Coord {
+                row: from.row,
+                col: to.col,
+            };
+            captured = self.get_piece(&pawn).ok().flatten().map(|p| p.piece);
+            self.remove_piece(&pawn);
+        }
+
+        // Castling: the king moves more than one cell towards a rook
+        let castle_right = match piece.piece == PieceType::King && (to.col - from.col).abs() > 1 {
+            true => self
+                .info
+                .castling
+                .get(&color)
+                .and_then(|rights| rights.iter().find(|right| right.new_king == *to).cloned()),
+            false => None,
+        };
+
+        self.move_to_coord(from, to);
+
+        if let Some(right) = &castle_right {
+            let rook_col = to.col + (from.col - to.col).signum();
+            self.move_to_coord(
+                &right.rook,
+                &Coord {
+                    row: to.row,
+                    col: rook_col,
+                },
+            );
+        }
+
+        let promotion = promotion
+            .filter(|_| piece.piece == PieceType::Pawn && self.is_promotion_row(to.row, color));
+        if let Some(promoted) = promotion.and_then(|p| promoted_piece(p, color, *to)) {
+            self.set_piece(promoted);
+        }
+
+        // Castling rights
+        if let Some(rights) = self.info.castling.get_mut(&color) {
+            match piece.piece {
+                PieceType::King => rights.clear(),
+                _ => rights.retain(|right| right.rook != *from),
+            }
+        }
+        if let Some(rights) = self.info.castling.get_mut(&color.opposite()) {
+            rights.retain(|right| right.rook != *to);
+        }
+
+        self.info.en_passant =
+            match piece.piece == PieceType::Pawn && (to.row - from.row).abs() == 2 {
+                true => Some(Coord {
+                    row: (from.row + to.row) / 2,
+                    col: from.col,
+                }),
+                false => None,
+            };
+
+        self.info.next_turn();
+        if piece.piece == PieceType::Pawn || captured.is_some() {
+            self.info.reset_halfmove_clock();
+        }
+
+        let record = MoveRecord {
+            from: *from,
+            to: *to,
+            color,
+            piece: piece.piece,
+            captured,
+            promotion,
+            castle: castle_right.is_some(),
+            en_passant,
+            gives_check: false,
+            discovered_check: false,
+            double_check: false,
+            uci: String::new(),
+        };
+        self.apply_effects(&record);
+        record
+    }
+
+    /// Fills the check flags of a move that was just applied
+    fn set_check_flags(&self, record: &mut MoveRecord) {
+        let mut checkers: Vec<Coord> = self
+            .royals(&record.color.opposite())
+            .iter()
+            .flat_map(|royal| self.attackers(royal, record.color))
+            .collect();
+        checkers.sort();
+        checkers.dedup();
+
+        // the castling rook lands next to the king, on the side it came from
+        let rook = Coord {
+            row: record.to.row,
+            col: record.to.col + (record.from.col - record.to.col).signum(),
+        };
+        let moved = |coord: &Coord| *coord == record.to || (record.castle && *coord == rook);
+
+        record.gives_check = !checkers.is_empty();
+        record.discovered_check = checkers.iter().any(|coord| !moved(coord));
+        record.double_check = checkers.len() >= 2;
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl MoveRecord {
+    #[getter(from_)]
+    fn py_from(&self) -> Coord {
+        self.from
+    }
+
+    #[getter(to)]
+    fn py_to(&self) -> Coord {
+        self.to
+    }
+
+    #[getter(uci)]
+    fn py_uci(&self) -> String {
+        self.uci.clone()
+    }
+
+    #[getter(captured)]
+    fn py_captured(&self) -> Option<String> {
+        self.captured.map(|p| p.to_char().to_string())
+    }
+
+    #[getter(promotion)]
+    fn py_promotion(&self) -> Option<String> {
+        self.promotion.map(|p| p.to_char().to_string())
+    }
+
+    #[getter(is_capture)]
+    fn py_is_capture(&self) -> bool {
+        self.is_capture()
+    }
+
+    #[getter(gives_check)]
+    fn py_gives_check(&self) -> bool {
+        self.gives_check
+    }
+
+    #[getter(is_discovered_check)]
+    fn py_is_discovered_check(&self) -> bool {
+        self.discovered_check
+    }
+
+    #[getter(is_double_check)]
+    fn py_is_double_check(&self) -> bool {
+        self.double_check
+    }
+
+    fn __str__(&self) -> String {
+        self.uci.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_uci() {
+        let mut board = Board::default();
+
+        let record = board.push_uci("e2e4").unwrap();
+        assert_eq!(record.piece, PieceType::Pawn);
+        assert_eq!(record.uci, "e2e4");
+        assert_eq!(board.info.turn, Color::Black);
+        assert_eq!(board.info.en_passant, Some(Coord { row: 5, col: 4 }));
+
+        assert!(matches!(
+            board.push_uci("e2e4"),
+            Err(MoveError::IllegalMove(_))
+        ));
+        assert!(matches!(
+            board.push_uci("e7"),
+            Err(MoveError::InvalidUci(_))
+        ));
+        assert!(matches!(
+            board.push_uci("e7e5x"),
+            Err(MoveError::InvalidUci(_))
+        ));
+
+        board.push_uci("e7e5").unwrap();
+        board.push_uci("g1f3").unwrap();
+        assert_eq!(board.info.halfmove_clock, 1);
+        assert_eq!(board.info.fullmove_number, 2);
+        assert_eq!(board.info.en_passant, None);
+
+        let expected =
+            Board::from_fen("rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2")
+                .unwrap();
+        assert!(board == expected);
+    }
+
+    #[test]
+    fn test_push_san() {
+        let mut board = Board::default();
+        for san in ["e4", "d5", "exd5", "Qxd5", "Nc3", "Qa5+"] {
+            board.push_san(san).unwrap();
+        }
+        assert_eq!(board.push_san("Bc4").unwrap().uci, "f1c4");
+
+        // c3 and g3 knights can both go to e4
+        let mut board = Board::from_fen("4k3/8/8/8/8/2N3N1/8/4K3 w - - 0 1").unwrap();
+        assert!(matches!(
+            board.push_san("Ne4"),
+            Err(MoveError::AmbiguousMove(_))
+        ));
+        assert_eq!(
+            board.push_san("Nce4").unwrap().from,
+            Coord { row: 5, col: 2 }
+        );
+
+        assert!(matches!(
+            board.push_san("Zz9"),
+            Err(MoveError::InvalidSan(_))
+        ));
+
+        // 10x10 board
+        let mut board = Board::from_fen("4k5/10/10/10/10/10/10/10/4K5/R8R w - - 0 1").unwrap();
+        assert!(matches!(
+            board.push_san("Rf1"),
+            Err(MoveError::AmbiguousMove(_))
+        ));
+        assert_eq!(board.push_san("Rjf1").unwrap().uci, "j1f1");
+        assert_eq!(board.push_san("Kd10").unwrap().uci, "e10d10");
+        assert_eq!(board.push_san("Ra10+").unwrap().uci, "a1a10");
+    }
+
+    #[test]
+    fn test_castle() {
+        let mut board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+        let record = board.push_san("O-O").unwrap();
+        assert!(record.castle);
+        assert_eq!(record.uci, "e1g1");
+        assert_eq!(
+            board
+                .get_piece(&Coord { row: 7, col: 5 })
+                .unwrap()
+                .unwrap()
+                .piece,
+            PieceType::Rook
+        );
+
+        board.push_uci("e8c8").unwrap();
+        assert_eq!(
+            board
+                .get_piece(&Coord { row: 0, col: 3 })
+                .unwrap()
+                .unwrap()
+                .piece,
+            PieceType::Rook
+        );
+
+        let expected = Board::from_fen("2kr3r/8/8/8/8/8/8/R4RK1 w - - 2 2").unwrap();
+        assert!(board == expected);
+    }
+
+    #[test]
+    fn test_en_passant_and_promotion() {
+        let mut board = Board::from_fen("4k3/1P6/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+
+        let record = board.push_uci("e5d6").unwrap();
+        assert!(record.en_passant);
+        assert_eq!(record.captured, Some(PieceType::Pawn));
+        assert!(board
+            .get_piece(&Coord { row: 3, col: 3 })
+            .unwrap()
+            .is_none());
+
+        board.push_uci("e8f7").unwrap();
+        assert!(matches!(
+            board.push_uci("b7b8"),
+            Err(MoveError::IllegalMove(_))
+        ));
+        let record = board.push_san("b8=N").unwrap();
+        assert_eq!(record.promotion, Some(PieceType::Knight));
+        assert_eq!(
+            board
+                .get_piece(&Coord { row: 0, col: 1 })
+                .unwrap()
+                .unwrap()
+                .piece,
+            PieceType::Knight
+        );
+    }
+
+    #[test]
+    fn test_check_flags() {
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        let record = board.push_uci("a1a8").unwrap();
+        assert!(record.gives_check && !record.discovered_check && !record.double_check);
+        assert!(!record.is_capture());
+
+        // the e4 knight uncovers the e1 rook
+        let board = Board::from_fen("4k3/8/8/8/4N3/8/8/4R1K1 w - - 0 1").unwrap();
+        let record = board.fork().push_uci("e4c5").unwrap();
+        assert!(record.gives_check && record.discovered_check && !record.double_check);
+        let record = board.fork().push_uci("e4d6").unwrap();
+        assert!(record.discovered_check && record.double_check);
+
+        // the castling rook checks, it is not a discovered check
+        let mut board = Board::from_fen("5k2/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        let record = board.push_uci("e1g1").unwrap();
+        assert!(record.castle && record.gives_check && !record.discovered_check);
+    }
+
+    #[test]
+    fn test_pop() {
+        let mut board = Board::default();
+        assert_eq!(board.pop(), None);
+
+        for uci in ["e2e4", "d7d5", "e4d5", "g8f6"] {
+            board.push_uci(uci).unwrap();
+        }
+        assert_eq!(board.move_history().len(), 4);
+
+        assert_eq!(board.pop().unwrap().uci, "g8f6");
+        let record = board.pop().unwrap();
+        assert_eq!(record.captured, Some(PieceType::Pawn));
+
+        let expected =
+            Board::from_fen("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2")
+                .unwrap();
+        assert!(board == expected);
+        assert_eq!(board.info.fullmove_number, 2);
+
+        // an illegal move is not recorded
+        let mut board = Board::from_fen("4r1k1/8/8/8/8/8/4B3/4K3 w - - 0 1").unwrap();
+        assert!(board.push_uci("e2d3").is_err());
+        assert_eq!(board.pop(), None);
+    }
+
+    #[test]
+    fn test_null_move() {
+        let mut board =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+        let before = board.clone();
+
+        assert!(!board.unmake_null_move());
+        let record = board.make_null_move();
+        assert!(record.is_null());
+        assert_eq!(record.uci, "0000");
+        assert_eq!(board.info.turn, Color::White);
+        assert_eq!(board.info.en_passant, None);
+        assert_eq!(board.info.fullmove_number, 1);
+
+        board.push_uci("d2d4").unwrap();
+        assert!(!board.unmake_null_move());
+        board.pop();
+
+        assert!(board.unmake_null_move());
+        assert!(board == before);
+    }
+
+    #[test]
+    fn test_mobility() {
+        let board = Board::default();
+        let map = board.mobility_map();
+        // b1 knight, e2 pawn, stuck rook, and the same for black
+        assert_eq!((map[7][1], map[6][4], map[7][0]), (2, 2, 0));
+        assert_eq!((map[0][1], map[1][4]), (2, 2));
+        assert_eq!(map.iter().flatten().sum::<u32>(), 40);
+
+        // Black to move: the white pieces still get their moves, and the pinned
+        // e2 bishop has none
+        let board = Board::from_fen("4r1k1/8/8/8/8/8/4B3/4K3 b - - 0 1").unwrap();
+        assert!(board.legal_moves(&Coord { row: 6, col: 4 }).is_empty());
+        assert_eq!(board.legal_moves(&Coord { row: 7, col: 4 }).len(), 4);
+        assert!(board.legal_moves(&Coord { row: 4, col: 4 }).is_empty());
+    }
+
+    #[test]
+    fn test_three_check() {
+        let mut board =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 3+1 0 1")
+                .unwrap();
+
+        for uci in ["e2e4", "f7f6", "d1h5"] {
+            board.push_uci(uci).unwrap();
+        }
+        assert_eq!(board.info.checks_given(Color::White), 1);
+        assert_eq!(board.info.check_limit_winner(), None);
+
+        board.pop();
+        assert_eq!(board.info.checks_given(Color::White), 0);
+
+        // Black gave two checks already, the third one wins
+        assert_eq!(board.info.remaining_checks(Color::Black), Some(1));
+        board.push_uci("g1f3").unwrap();
+        board.push_uci("e8f7").unwrap();
+        board.push_uci("f3e5").unwrap();
+        assert_eq!(board.info.checks_given(Color::White), 1);
+        board.push_uci("f6e5").unwrap();
+        for uci in ["d2d4", "e7e6", "h2h3", "f8b4"] {
+            board.push_uci(uci).unwrap();
+        }
+        assert_eq!(board.info.check_limit_winner(), Some(Color::Black));
+
+        // standard games don't count checks
+        let mut board = Board::default();
+        for uci in ["e2e4", "f7f6", "d1h5"] {
+            board.push_uci(uci).unwrap();
+        }
+        assert_eq!(board.info.checks_given(Color::White), 0);
+    }
+
+    #[test]
+    fn test_king_safety() {
+        // The e2 bishop is pinned
+        let mut board = Board::from_fen("4r1k1/8/8/8/8/8/4B3/4K3 w - - 0 1").unwrap();
+
+        assert!(!board.is_legal(&Coord { row: 6, col: 4 }, &Coord { row: 5, col: 3 }));
+        assert!(matches!(
+            board.push_uci("e2d3"),
+            Err(MoveError::IllegalMove(_))
+        ));
+        assert!(board.push_uci("e1d2").is_ok());
+    }
+}
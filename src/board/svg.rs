@@ -0,0 +1,202 @@
+use super::{Board, Coord};
+use crate::notation::file_to_str;
+use crate::piece::Color;
+
+/// Side of a cell, in pixels
+const CELL: i32 = 45;
+/// Space for the rank and file labels
+const MARGIN: i32 = 20;
+
+const LIGHT: &str = "#f0d9b5";
+const DARK: &str = "#b58863";
+/// Drawn over the cells of the last move
+const LAST_MOVE: &str = "#cdd26a";
+
+impl Board {
+    /// The board as an SVG image: cells, pieces, rank and file labels, the
+    /// last move played with `push` highlighted and the board's annotations
+    pub fn to_svg(&self) -> String {
+        let (rows, cols) = (self.get_rows() as i32, self.get_cols() as i32);
+        let (width, height) = (cols * CELL + MARGIN, rows * CELL + MARGIN);
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+            w = width,
+            h = height
+        );
+
+        let last_move: Vec<Coord> = match self.move_history().last() {
+            Some(record) if !record.is_null() => vec![record.from, record.to],
+            _ => vec![],
+        };
+
+        for row in 0..rows {
+            for col in 0..cols {
+                let (x, y) = (MARGIN + col * CELL, row * CELL);
+                let coord = Coord { row, col };
+
+                let fill = match (last_move.contains(&coord), (row + col) % 2 == 0) {
+                    (true, _) => LAST_MOVE,
+                    (false, true) => LIGHT,
+                    (false, false) => DARK,
+                };
+                svg.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{c}\" height=\"{c}\" fill=\"{}\"/>",
+                    x,
+                    y,
+                    fill,
+                    c = CELL
+                ));
+
+                for (_, color) in self
+                    .annotations
+                    .highlights
+                    .iter()
+                    .filter(|(c, _)| *c == coord)
+                {
+                    svg.push_str(&format!(
+                        "<rect x=\"{}\" y=\"{}\" width=\"{c}\" height=\"{c}\" fill=\"{}\" fill-opacity=\"0.5\"/>",
+                        x,
+                        y,
+                        escape(color),
+                        c = CELL
+                    ));
+                }
+
+                if let Ok(Some(piece)) = self.get_piece(&coord) {
+                    // Solid glyphs for both colors, white ones with an outline
+                    let (fill, stroke) = match piece.color {
+                        Color::White => ("#ffffff", "#000000"),
+                        Color::Black => ("#000000", "none"),
+                    };
+                    svg.push_str(&format!(
+                        "<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" \
+                         dominant-baseline=\"central\" fill=\"{}\" stroke=\"{}\">{}</text>",
+                        x + CELL / 2,
+                        y + CELL / 2,
+                        CELL * 4 / 5,
+                        fill,
+                        stroke,
+                        piece.piece
+                    ));
+                }
+            }
+
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"12\" text-anchor=\"middle\" \
+                 dominant-baseline=\"central\">{}</text>",
+                MARGIN / 2,
+                row * CELL + CELL / 2,
+                rows - row
+            ));
+        }
+
+        for col in 0..cols {
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"12\" text-anchor=\"middle\" \
+                 dominant-baseline=\"central\">{}</text>",
+                MARGIN + col * CELL + CELL / 2,
+                rows * CELL + MARGIN / 2,
+                file_to_str(col as u32)
+            ));
+        }
+
+        for (coord, text) in &self.annotations.labels {
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"10\" fill=\"#000000\">{}</text>",
+                MARGIN + coord.col * CELL + 2,
+                coord.row * CELL + 10,
+                escape(text)
+            ));
+        }
+
+        for (from, to, color) in &self.annotations.arrows {
+            svg.push_str(&arrow(from, to, color));
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+/// Center of a cell, in pixels
+fn center(coord: &Coord) -> (f64, f64) {
+    (
+        (MARGIN + coord.col * CELL + CELL / 2) as f64,
+        (coord.row * CELL + CELL / 2) as f64,
+    )
+}
+
+/// Line between the centers of both cells, with a triangle as its head
+fn arrow(from: &Coord, to: &Coord, color: &str) -> String {
+    let ((x1, y1), (x2, y2)) = (center(from), center(to));
+    let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+    if length == 0.0 {
+        return String::new();
+    }
+
+    // unit vector along the arrow and its normal
+    let (dx, dy) = ((x2 - x1) / length, (y2 - y1) / length);
+    let head = CELL as f64 / 3.0;
+    let (bx, by) = (x2 - dx * head, y2 - dy * head);
+    let (nx, ny) = (-dy * head / 2.0, dx * head / 2.0);
+
+    format!(
+        "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{c}\" stroke-width=\"{}\" stroke-opacity=\"0.8\"/>\
+         <polygon points=\"{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}\" fill=\"{c}\" fill-opacity=\"0.8\"/>",
+        x1,
+        y1,
+        bx,
+        by,
+        CELL / 6,
+        x2,
+        y2,
+        bx + nx,
+        by + ny,
+        bx - nx,
+        by - ny,
+        c = escape(color)
+    )
+}
+
+/// Escapes text and attribute values (annotation colors come from users)
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg() {
+        let mut board = Board::default();
+        let svg = board.to_svg();
+
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("<rect").count(), 64);
+        // 32 pieces, 8 ranks and 8 files
+        assert_eq!(svg.matches("<text").count(), 48);
+        assert!(!svg.contains(LAST_MOVE));
+
+        board.push_uci("e2e4").unwrap();
+        assert_eq!(board.to_svg().matches(LAST_MOVE).count(), 2);
+    }
+
+    #[test]
+    fn test_escaped_colors() {
+        let mut board = Board::default();
+        let e2 = Coord { row: 6, col: 4 };
+        let e4 = Coord { row: 4, col: 4 };
+        let color = "red\" onload=\"alert(1)";
+        board.highlight(e2, color);
+        board.add_arrow(e2, e4, color);
+
+        let svg = board.to_svg();
+        assert!(!svg.contains("onload=\""));
+        assert_eq!(svg.matches("red&quot; onload=&quot;alert(1)").count(), 3);
+    }
+}
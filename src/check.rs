@@ -18,23 +18,24 @@ pub fn is_check(coord: &Coord, board: &mut Board, is_checking_mate: bool) -> boo
         _ => return false, // no piece under check in that cell
     };
 
-    let ally_king_coord = board.get_king(&enemy_color.opposite()).coord;
+    let ally_color = enemy_color.opposite();
 
-    // fn checks that after an ally move, the ally king is not under check
+    // fn checks that after an ally move, no ally royal piece is under check
     let ally_in_check = |board: &mut Board| -> bool {
         let enemy_pieces = board.get_all_pieces(&enemy_color);
 
-        return enemy_pieces
-            .iter()
-            .any(|enemy_piece| enemy_piece.can_move(ally_king_coord.clone(), &board));
+        return board.royals(&ally_color).iter().any(|royal| {
+            enemy_pieces
+                .iter()
+                .any(|enemy_piece| enemy_piece.can_move(*royal, &board))
+        });
     };
 
-    for piece in board.clone().get_all_pieces(&enemy_color.opposite()) {
+    for piece in board.clone().get_all_pieces(&ally_color) {
         // if an *ally* piece can move to the cell that might be under check.
         // BUT ...
         if piece.can_move(coord.clone(), &board) {
-            let is_ally_in_check =
-                board.temporal_move(&piece.coord, &ally_king_coord, ally_in_check);
+            let is_ally_in_check = board.temporal_move(&piece.coord, coord, ally_in_check);
 
             // if after moving an ally, the ally king is not under check, then the move is legal
             if !is_ally_in_check || is_checking_mate {
@@ -0,0 +1,361 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::piece::Color;
+#[cfg(feature = "python")]
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+////////////////////////////////////////////////
+// TIME CONTROL
+////////////////////////////////////////////////
+
+/// Base time + increment per move (Fischer). An increment of 0 is sudden death.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "python", pyclass)]
+pub struct TimeControl {
+    pub base: Duration,
+    pub increment: Duration,
+}
+
+impl TimeControl {
+    pub fn new(base: Duration, increment: Duration) -> Self {
+        Self { base, increment }
+    }
+
+    pub fn sudden_death(base: Duration) -> Self {
+        Self::new(base, Duration::ZERO)
+    }
+
+    pub fn is_sudden_death(&self) -> bool {
+        self.increment.is_zero()
+    }
+}
+
+////////////////////////////////////////////////
+// CLOCK
+////////////////////////////////////////////////
+
+/// Time spent on a single move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoveTime {
+    pub color: Color,
+    pub elapsed: Duration,
+    /// Time left on the mover's clock after the move (increment included)
+    pub remaining: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClockError {
+    /// The color ran out of time
+    Flagged(Color),
+}
+
+#[cfg(feature = "python")]
+impl std::convert::From<ClockError> for PyErr {
+    fn from(err: ClockError) -> PyErr {
+        PyValueError::new_err(format!("{:?}", err))
+    }
+}
+
+/// Chess clock driven by the game loop: each move reports how long it took.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "python", pyclass)]
+pub struct Clock {
+    pub control: TimeControl,
+    remaining: HashMap<Color, Duration>,
+    history: Vec<MoveTime>,
+    flagged: Option<Color>,
+}
+
+impl Clock {
+    pub fn new(control: TimeControl) -> Self {
+        let mut remaining = HashMap::new();
+        remaining.insert(Color::White, control.base);
+        remaining.insert(Color::Black, control.base);
+
+        Self {
+            control,
+            remaining,
+            history: Vec::new(),
+            flagged: None,
+        }
+    }
+
+    pub fn remaining(&self, color: &Color) -> Duration {
+        self.remaining[color]
+    }
+
+    /// Color that ran out of time, if any
+    pub fn flagged(&self) -> Option<Color> {
+        self.flagged
+    }
+
+    /// Time spent on every recorded move, in order
+    pub fn history(&self) -> &[MoveTime] {
+        &self.history
+    }
+
+    /// Records a move of `color` that took `elapsed`.
+    ///
+    /// The increment is only added if the move was made in time. Once a side
+    /// has flagged the clock is stopped and every call returns the flag.
+    pub fn record_move(&mut self, color: Color, elapsed: Duration) -> Result<Duration, ClockError> {
+        if let Some(flagged) = self.flagged {
+            return Err(ClockError::Flagged(flagged));
+        }
+
+        let left = self.remaining[&color];
+
+        if elapsed > left {
+            self.flagged = Some(color);
+            self.remaining.insert(color, Duration::ZERO);
+            return Err(ClockError::Flagged(color));
+        }
+
+        let remaining = left - elapsed + self.control.increment;
+        self.remaining.insert(color, remaining);
+        self.history.push(MoveTime {
+            color,
+            elapsed,
+            remaining,
+        });
+
+        Ok(remaining)
+    }
+}
+
+////////////////////////////////////////////////
+// LATENCY
+////////////////////////////////////////////////
+
+/// Delay added to the thinking time of each move, like the network or a GUI
+/// would: `base` plus a pseudo-random share of `jitter`. The sequence only
+/// depends on the seed, so simulated games can be replayed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "python", pyclass)]
+pub struct Latency {
+    pub base: Duration,
+    pub jitter: Duration,
+    state: u64,
+}
+
+impl Latency {
+    pub fn new(base: Duration, jitter: Duration, seed: u64) -> Self {
+        Self {
+            base,
+            jitter,
+            // xorshift gets stuck at 0
+            state: seed.max(1),
+        }
+    }
+
+    /// No delay at all
+    pub fn none() -> Self {
+        Self::new(Duration::ZERO, Duration::ZERO, 1)
+    }
+
+    /// Delay of the next move
+    pub fn sample(&mut self) -> Duration {
+        // xorshift64
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+
+        let share = (self.state >> 11) as f64 / (1u64 << 53) as f64;
+        self.base + self.jitter.mul_f64(share)
+    }
+}
+
+impl Clock {
+    /// Records a move that took `thinking` plus the next delay of `latency`,
+    /// see `record_move`
+    pub fn record_move_with_latency(
+        &mut self,
+        color: Color,
+        thinking: Duration,
+        latency: &mut Latency,
+    ) -> Result<Duration, ClockError> {
+        self.record_move(color, thinking + latency.sample())
+    }
+
+    /// Time features for the observation of `color`: its remaining time, the
+    /// opponent's and the increment, as shares of the base time
+    pub fn time_features(&self, color: &Color) -> [f64; 3] {
+        let base = self.control.base.as_secs_f64();
+        if base == 0.0 {
+            return [0.0; 3];
+        }
+
+        [
+            self.remaining(color).as_secs_f64() / base,
+            self.remaining(&color.opposite()).as_secs_f64() / base,
+            self.control.increment.as_secs_f64() / base,
+        ]
+    }
+}
+
+////////////////////////////////////////////////
+// PYTHON
+////////////////////////////////////////////////
+
+/// Times are exchanged with Python as seconds
+#[cfg(feature = "python")]
+fn seconds(secs: f64) -> PyResult<Duration> {
+    Duration::try_from_secs_f64(secs)
+        .map_err(|_| PyValueError::new_err(format!("Invalid time {}", secs)))
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl TimeControl {
+    #[new]
+    #[pyo3(signature = (base, increment = 0.0))]
+    fn py_new(base: f64, increment: f64) -> PyResult<Self> {
+        Ok(Self::new(seconds(base)?, seconds(increment)?))
+    }
+
+    #[getter(base)]
+    fn py_base(&self) -> f64 {
+        self.base.as_secs_f64()
+    }
+
+    #[getter(increment)]
+    fn py_increment(&self) -> f64 {
+        self.increment.as_secs_f64()
+    }
+
+    #[pyo3(name = "is_sudden_death")]
+    fn py_is_sudden_death(&self) -> bool {
+        self.is_sudden_death()
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Clock {
+    #[new]
+    fn py_new(control: TimeControl) -> Self {
+        Self::new(control)
+    }
+
+    /// Seconds left for `color`
+    #[pyo3(name = "remaining")]
+    fn py_remaining(&self, color: Color) -> f64 {
+        self.remaining(&color).as_secs_f64()
+    }
+
+    #[getter(flagged)]
+    fn py_flagged(&self) -> Option<Color> {
+        self.flagged()
+    }
+
+    /// Records a move that took `elapsed` seconds, returns the seconds left.
+    /// Raises ValueError if `color` flagged.
+    #[pyo3(name = "record_move")]
+    fn py_record_move(&mut self, color: Color, elapsed: f64) -> PyResult<f64> {
+        Ok(self.record_move(color, seconds(elapsed)?)?.as_secs_f64())
+    }
+
+    #[pyo3(name = "record_move_with_latency")]
+    fn py_record_move_with_latency(
+        &mut self,
+        color: Color,
+        thinking: f64,
+        mut latency: PyRefMut<Latency>,
+    ) -> PyResult<f64> {
+        let left = self.record_move_with_latency(color, seconds(thinking)?, &mut latency)?;
+        Ok(left.as_secs_f64())
+    }
+
+    #[pyo3(name = "time_features")]
+    fn py_time_features(&self, color: Color) -> [f64; 3] {
+        self.time_features(&color)
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Latency {
+    #[new]
+    #[pyo3(signature = (base = 0.0, jitter = 0.0, seed = 1))]
+    fn py_new(base: f64, jitter: f64, seed: u64) -> PyResult<Self> {
+        Ok(Self::new(seconds(base)?, seconds(jitter)?, seed))
+    }
+
+    /// Seconds of delay of the next move
+    #[pyo3(name = "sample")]
+    fn py_sample(&mut self) -> f64 {
+        self.sample().as_secs_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_increment() {
+        let control = TimeControl::new(Duration::from_secs(60), Duration::from_secs(2));
+        let mut clock = Clock::new(control);
+
+        let left = clock
+            .record_move(Color::White, Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(left, Duration::from_secs(52));
+        assert_eq!(clock.remaining(&Color::Black), Duration::from_secs(60));
+        assert_eq!(clock.history().len(), 1);
+        assert_eq!(clock.flagged(), None);
+    }
+
+    #[test]
+    fn test_flag() {
+        let control = TimeControl::sudden_death(Duration::from_secs(5));
+        let mut clock = Clock::new(control);
+
+        assert!(control.is_sudden_death());
+        assert!(clock
+            .record_move(Color::White, Duration::from_secs(5))
+            .is_ok());
+        assert_eq!(clock.remaining(&Color::White), Duration::ZERO);
+
+        assert_eq!(
+            clock.record_move(Color::Black, Duration::from_secs(6)),
+            Err(ClockError::Flagged(Color::Black))
+        );
+        assert_eq!(clock.flagged(), Some(Color::Black));
+
+        // clock is stopped
+        assert_eq!(
+            clock.record_move(Color::White, Duration::ZERO),
+            Err(ClockError::Flagged(Color::Black))
+        );
+        assert_eq!(clock.history().len(), 1);
+    }
+
+    #[test]
+    fn test_latency() {
+        let control = TimeControl::new(Duration::from_secs(10), Duration::from_secs(1));
+        let mut clock = Clock::new(control);
+        let mut latency = Latency::new(Duration::from_millis(100), Duration::from_millis(50), 7);
+
+        let delays: Vec<Duration> = (0..20).map(|_| latency.sample()).collect();
+        assert!(delays
+            .iter()
+            .all(|d| *d >= Duration::from_millis(100) && *d < Duration::from_millis(150)));
+        assert_ne!(delays[0], delays[1]);
+
+        // same seed, same delays
+        let mut latency = Latency::new(Duration::from_millis(100), Duration::from_millis(50), 7);
+        let left = clock
+            .record_move_with_latency(Color::White, Duration::from_secs(2), &mut latency)
+            .unwrap();
+        assert_eq!(left, Duration::from_secs(9) - delays[0]);
+
+        let features = clock.time_features(&Color::Black);
+        assert_eq!(features[1], left.as_secs_f64() / 10.0);
+        assert_eq!((features[0], features[2]), (1.0, 0.1));
+
+        let mut none = Latency::none();
+        assert_eq!(none.sample(), Duration::ZERO);
+    }
+}
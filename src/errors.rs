@@ -1,9 +1,12 @@
+#[cfg(feature = "python")]
 use pyo3::exceptions::PyIndexError;
+#[cfg(feature = "python")]
 use pyo3::{pyclass, PyErr};
 #[derive(Debug, Clone, PartialEq)]
-#[pyclass]
+#[cfg_attr(feature = "python", pyclass)]
 pub struct OutOfBoundsError;
 
+#[cfg(feature = "python")]
 impl std::convert::From<OutOfBoundsError> for PyErr {
     fn from(err: OutOfBoundsError) -> PyErr {
         PyIndexError::new_err("Index out of bounds")
@@ -3,20 +3,62 @@ pub mod errors;
 pub mod moves;
 pub mod notation;
 pub mod piece;
+pub mod registry;
 pub mod check;
+pub mod clock;
+pub mod openings;
+pub mod wdl;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 pub use board::{Board, Coord};
 pub use piece::{Piece, PieceType};
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 pub fn add(left: usize, right: usize) -> usize {
     left + right
 }
 
+/// Positions, distinct positions and duplicate ratio of a list of FENs, see
+/// `board::duplicate_report`
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "duplicate_report")]
+fn py_duplicate_report(fens: Vec<String>) -> PyResult<(usize, usize, f64)> {
+    let fens: Vec<&str> = fens.iter().map(String::as_str).collect();
+    let report = board::duplicate_report(&fens)?;
+    Ok((report.total, report.unique, report.duplicate_ratio()))
+}
+
+/// Indices of the first occurrence of each position of a list of FENs, see
+/// `board::dedup_positions`
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "dedup_positions", signature = (fens, symmetric = false))]
+fn py_dedup_positions(fens: Vec<String>, symmetric: bool) -> PyResult<Vec<usize>> {
+    let mut boards = vec![];
+    for fen in &fens {
+        boards.push(board::Board::from_fen(fen)?);
+    }
+    Ok(board::dedup_positions(&boards, symmetric))
+}
+
 /// A Python module implemented in Rust. The name of this function must match
 /// the `lib.name` setting in the `Cargo.toml`, else Python will not be able to
 /// import the module.
+#[cfg(feature = "python")]
 #[pymodule]
 fn chess_model(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<Board>()?;
+    m.add_class::<board::Handicap>()?;
+    m.add_class::<board::Bitboard>()?;
+    m.add_class::<board::MoveRecord>()?;
+    m.add_class::<board::PositionDescriptor>()?;
+    m.add_class::<piece::Color>()?;
+    m.add_class::<clock::TimeControl>()?;
+    m.add_class::<clock::Clock>()?;
+    m.add_class::<clock::Latency>()?;
+    m.add_function(wrap_pyfunction!(py_duplicate_report, m)?)?;
+    m.add_function(wrap_pyfunction!(py_dedup_positions, m)?)?;
     Ok(())
 }
 
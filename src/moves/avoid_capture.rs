@@ -0,0 +1,77 @@
+use super::Move;
+use crate::board::{Board, Coord};
+
+/// Wraps a move so it never goes to a cell attacked by the enemy, e.g. for
+/// kings. The piece is taken off its cell first, so it can't hide from a
+/// slider behind itself.
+///
+/// It only filters destinations: a move that exposes another royal piece is
+/// still caught by `Board::is_legal`.
+pub struct AvoidCapture<M: Move> {
+    inner: M,
+}
+
+impl<M: Move> AvoidCapture<M> {
+    pub fn new(inner: M) -> Self {
+        Self { inner }
+    }
+
+    fn is_safe(&self, from: Coord, to: Coord, board: &Board) -> bool {
+        let color = match board.get_piece(&from) {
+            Ok(Some(piece)) => piece.color,
+            _ => return false,
+        };
+
+        let mut board = board.fork();
+        board.remove_piece(&from);
+        !board.is_square_attacked(&to, color.opposite())
+    }
+}
+
+impl<M: Move> Move for AvoidCapture<M> {
+    fn is_move_valid(&self, from: Coord, to: Coord, board: &Board) -> bool {
+        self.inner.is_move_valid(from, to, board) && self.is_safe(from, to, board)
+    }
+
+    fn allowed_moves(&self, from: Coord, board: &Board) -> Vec<Coord> {
+        self.inner
+            .allowed_moves(from, board)
+            .into_iter()
+            .filter(|to| self.is_safe(from, *to, board))
+            .collect()
+    }
+
+    /// The piece still attacks the cells it can't move to
+    fn attacks(&self, from: Coord, to: Coord, board: &Board) -> bool {
+        self.inner.attacks(from, to, board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_king_avoids_attacked_cells() {
+        // e1 king: d1 and d2 are attacked by the d8 rook, f1 by the a1 rook
+        // through the king's own cell, and the f2 knight is defended by g3
+        let board = Board::from_fen("3rk3/8/8/8/8/6p1/5n2/r3K3 w - - 0 1").unwrap();
+        let e1 = Coord::from_rank_file(0, 4, 8);
+
+        assert_eq!(
+            board.get_moves(&e1, false),
+            [Coord::from_rank_file(1, 4, 8)]
+        );
+        assert!(!board.can_move(&e1, &Coord::from_rank_file(1, 5, 8), false));
+    }
+
+    #[test]
+    fn test_kings_next_to_each_other() {
+        // 6x6 boards take the generic attack path, which must not recurse
+        // between both kings
+        let board = Board::from_fen("6/6/2k3/6/2K3/6 w - - 0 1").unwrap();
+        let moves = board.get_moves(&Coord { row: 4, col: 2 }, false);
+        assert_eq!(moves.len(), 5);
+        assert!(moves.iter().all(|to| to.row == 4 || to.row == 5));
+    }
+}
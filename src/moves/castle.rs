@@ -4,7 +4,11 @@ use crate::{
     piece::Piece,
 };
 
-use super::{parse_direction, Line, Move};
+use super::{
+    parse_direction,
+    util::{direction_iter, ray_between},
+    Line, Move,
+};
 const MAX_RANGE: u32 = 2; // In a FIDE castle, the king can move 2 cells
 pub struct Castle<M: Move> {
     movement: M,
@@ -19,28 +23,14 @@ impl Castle<Line> {
     }
 
     fn is_line_clear(&self, king: &Coord, rook: &Coord, board: &Board) -> bool {
-        let direction = match parse_direction(king, rook) {
-            Ok(direction) => direction,
-            Err(_) => return false,
-        };
-
-        let step = direction.get_step();
-
-        let mut inter_cell = *king + step; // intermediate cell that the king will traverse
-
-        loop {
-            if inter_cell == *rook {
-                return true;
-            }
-
-            match board.get_piece(&inter_cell) {
-                Ok(Some(_)) => return false,
-                Ok(None) => (),
-                Err(_) => return true, //
-            }
-
-            inter_cell = inter_cell + step;
+        if parse_direction(king, rook).is_err() {
+            return false;
         }
+
+        // intermediate cells that the king will traverse
+        ray_between(king, rook)
+            .iter()
+            .all(|inter_cell| !matches!(board.get_piece(inter_cell), Ok(Some(_))))
     }
 
     /// Check that the king can safely traverse the path (there are not pieces in the way)
@@ -51,7 +41,7 @@ impl Castle<Line> {
         rook: &Coord,
         board: &mut Board,
     ) -> bool {
-        let direction = match parse_direction(new_king, king) {
+        let direction = match parse_direction(king, new_king) {
             Ok(direction) => direction,
             Err(_) => return false,
         };
@@ -70,13 +60,14 @@ impl Castle<Line> {
         };
 
         // if can move, check that the king is not under check in any of the
-        // inter cells
-        let step = direction.get_step();
-
-        let mut inter_cell = *king; // intermediate cell that the king will traverse
+        // inter cells (intermediate cells that the king will traverse)
+        let inter_cells = std::iter::once(*king).chain(direction_iter(king, &direction));
 
-        for _ in 0..MAX_RANGE + 1 {
+        for inter_cell in inter_cells.take(MAX_RANGE as usize + 1) {
             // + 1 because the king cannot move under check in the initial cell
+            if !board.in_bounds(&inter_cell) {
+                return false; // castling rights that don't match the king's cell
+            }
 
             let under_check = board.temporal_move(king, &inter_cell, |board| {
                 is_check(&inter_cell, board, false)
@@ -85,8 +76,6 @@ impl Castle<Line> {
             if under_check {
                 return false;
             }
-
-            inter_cell = inter_cell.add(&step);
         }
         true
     }
@@ -210,6 +199,30 @@ mod tests {
         assert!(!castle.is_move_valid(from, to, &board));
     }
 
+    #[test]
+    fn test_castle_through_check() {
+        // f1 is attacked, so only the long castle is valid
+        let board = Board::from_fen("4kr2/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+        let castle = Castle::new(None);
+        let from = Coord { row: 7, col: 4 };
+
+        assert!(castle.is_move_valid(from, Coord { row: 7, col: 2 }, &board));
+        assert!(!castle.is_move_valid(from, Coord { row: 7, col: 6 }, &board));
+    }
+
+    #[test]
+    fn test_rights_without_king() {
+        // The FEN keeps the rights, but the king is not in its initial cell
+        // and c1 is diagonal to it: the path used to walk off the board
+        let board = Board::from_fen("4k3/8/8/8/8/8/1K6/R6R w KQ - 0 1").unwrap();
+        let castle = Castle::new(None);
+
+        assert!(castle
+            .allowed_moves(Coord { row: 6, col: 1 }, &board)
+            .is_empty());
+        assert_eq!(board.move_gen(None, &[]).count(), 33);
+    }
+
     #[test]
     fn test_generate_valid() {
         let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
@@ -32,14 +32,12 @@ impl Move for Diagonal {
             },
             _ => return false,
         };
-        let step = direction.get_step();
-
 
         let max_range = self
             .max_range
             .unwrap_or(board.max_cells_direction(&direction));
 
-        return can_traverse(board, from_piece, &to, &step, max_range);
+        return can_traverse(board, from_piece, &to, max_range);
     }
 
     fn allowed_moves(&self, from: Coord, board: &Board) -> Vec<Coord> {
@@ -74,15 +72,15 @@ impl Move for Diagonal {
 }
 
 mod tests {
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     use super::*;
     use crate::board::{Board, Coord};
     use crate::piece::{Color, Piece, PieceType};
 
-    fn prepare(coord: Coord) -> (Board, Rc<Diagonal>, Coord) {
+    fn prepare(coord: Coord) -> (Board, Arc<Diagonal>, Coord) {
         let mut board = Board::new(Some(3), Some(3));
-        let movement = Rc::new(Diagonal::new(None));
+        let movement = Arc::new(Diagonal::new(None));
 
         let piece = Piece::new(
             Color::Black,
@@ -157,7 +155,7 @@ mod tests {
     #[test]
     fn test_bishop_center_board() {
         let mut board = Board::default();
-        let movement = Rc::new(Diagonal::new(None));
+        let movement = Arc::new(Diagonal::new(None));
 
         let bishop = Piece::new(
             Color::Black,
@@ -178,7 +176,7 @@ mod tests {
         let board = Board::default();
 
         let from = Coord { row: 7, col: 2 };
-        let movement = Rc::new(Diagonal::new(None));
+        let movement = Arc::new(Diagonal::new(None));
 
         let moves = movement.allowed_moves(from, &board);
         assert_eq!(moves.len(), 0);
@@ -187,7 +185,7 @@ mod tests {
     #[test]
     fn test_1_step() {
         let mut board = Board::default();
-        let movement = Rc::new(Diagonal::new(Some(1)));
+        let movement = Arc::new(Diagonal::new(Some(1)));
 
         let king = Piece::new(
             Color::Black,
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::BTreeSet;
 
 use crate::board::{Board, Coord};
 
@@ -57,7 +57,7 @@ impl Move for Jump {
             _ => return vec![],
         };
 
-        let mut to_coords = HashSet::new();
+        let mut to_coords = BTreeSet::new();
 
         for mask in FIRST_MOVE_MASK.iter() {
             // For each long* step, go N, S, E, W
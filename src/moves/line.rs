@@ -26,18 +26,18 @@ impl Move for Line {
             Err(_) => return false,
         };
 
-        let step = match direction {
-            Direction::North | Direction::South | Direction::East | Direction::West => {
-                direction.get_step()
-            }
-            _ => return false,
-        };
+        if !matches!(
+            direction,
+            Direction::North | Direction::South | Direction::East | Direction::West
+        ) {
+            return false;
+        }
 
         let max_range = self
             .max_range
             .unwrap_or(board.max_cells_direction(&direction));
 
-        return can_traverse(board, from_piece, &to, &step, max_range);
+        return can_traverse(board, from_piece, &to, max_range);
     }
 
     fn allowed_moves(&self, from: Coord, board: &Board) -> Vec<Coord> {
@@ -71,22 +71,22 @@ impl Move for Line {
 }
 
 mod tests {
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     use super::*;
     use crate::board::{Board, Coord};
     use crate::piece::{Color, Piece, PieceType};
 
-    fn prepare() -> (Board, Coord, Rc<dyn Move>) {
+    fn prepare() -> (Board, Coord, Arc<dyn Move>) {
         let mut board = Board::new(Some(3), Some(3));
         let from = Coord { row: 0, col: 0 };
 
-        let line: Rc<dyn Move> = Rc::new(Line::new(None));
-        let rook = Piece::new(Color::White, PieceType::Rook, vec![Rc::clone(&line)], from);
+        let line: Arc<dyn Move> = Arc::new(Line::new(None));
+        let rook = Piece::new(Color::White, PieceType::Rook, vec![Arc::clone(&line)], from);
 
         board.set_piece(rook);
 
-        return (board, from, Rc::clone(&line));
+        return (board, from, Arc::clone(&line));
     }
 
     #[test]
@@ -104,7 +104,7 @@ mod tests {
         ] {
             println!("Testing {:?} -> {:?}", from, to);
             assert!(line.is_move_valid(from, to, &board) == result);
-            assert!(board.can_move(&from, &to) == result);
+            assert!(board.can_move(&from, &to, false) == result);
         }
     }
 
@@ -173,7 +173,7 @@ mod tests {
 
         board.set_piece(king);
 
-        let line = Rc::new(Line::new(Some(1)));
+        let line = Arc::new(Line::new(Some(1)));
 
         let moves = line.allowed_moves(Coord { row: 3, col: 3 }, &board);
 
@@ -184,7 +184,7 @@ mod tests {
     fn test_initial_rook_moves() {
         let board = Board::default();
 
-        let line = Rc::new(Line::new(None));
+        let line = Arc::new(Line::new(None));
 
         let moves = line.allowed_moves(Coord { row: 0, col: 0 }, &board);
         assert_eq!(moves.len(), 0);
@@ -197,7 +197,7 @@ mod tests {
         let rook = Piece::new_rook(Color::White, Coord { row: 3, col: 3 });
         board.set_piece(rook.clone());
 
-        let line = Rc::new(Line::new(None));
+        let line = Arc::new(Line::new(None));
         let moves = line.allowed_moves(rook.coord, &board);
         println!("{:?}", board);
         assert_eq!(moves.len(), 11);
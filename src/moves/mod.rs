@@ -1,18 +1,24 @@
 use crate::piece::Piece;
 
 use super::board::{Board, Coord, HasCoordinates};
+pub mod avoid_capture;
 pub mod castle;
 pub mod diag;
 pub mod jump;
 pub mod line;
+pub mod modifiers;
 pub mod pawn;
-mod util;
+pub mod util;
 // Re-export the modules:
+pub use avoid_capture::AvoidCapture;
 pub use diag::Diagonal;
 pub use line::Line;
+pub use modifiers::{CaptureOnly, Directions, FirstMoveOnly, NoCapture, Repeat};
 pub use pawn::PawnMove;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
-pub trait Move {
+/// Moves are shared between pieces (and threads), so they must be stateless
+pub trait Move: Send + Sync {
     fn is_move_valid(&self, from: Coord, to: Coord, board: &Board) -> bool;
 
     /// Moves a piece from one cell to another
@@ -37,13 +43,43 @@ pub trait Move {
     }
     fn allowed_moves(&self, from: Coord, board: &Board) -> Vec<Coord>;
 
+    /// True if the piece in `from` could capture an enemy piece in `to`. The
+    /// same as `is_move_valid`, unless the move filters its destinations by
+    /// their safety (see `AvoidCapture`).
+    fn attacks(&self, from: Coord, to: Coord, board: &Board) -> bool {
+        self.is_move_valid(from, to, board)
+    }
+
     fn can_promote(&self, piece: &Piece, prom_coord: &Coord, board: &Board) -> bool {
         false
     }
 }
 
+/// Shared moves, e.g. the ones of `notation::betza::parse`, can be wrapped too
+impl<M: Move + ?Sized> Move for std::sync::Arc<M> {
+    fn is_move_valid(&self, from: Coord, to: Coord, board: &Board) -> bool {
+        (**self).is_move_valid(from, to, board)
+    }
+
+    fn move_piece(&self, from: Coord, to: Coord, board: &mut Board) {
+        (**self).move_piece(from, to, board)
+    }
+
+    fn allowed_moves(&self, from: Coord, board: &Board) -> Vec<Coord> {
+        (**self).allowed_moves(from, board)
+    }
+
+    fn attacks(&self, from: Coord, to: Coord, board: &Board) -> bool {
+        (**self).attacks(from, to, board)
+    }
+
+    fn can_promote(&self, piece: &Piece, prom_coord: &Coord, board: &Board) -> bool {
+        (**self).can_promote(piece, prom_coord, board)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
-#[pyclass]
+#[cfg_attr(feature = "python", pyclass)]
 pub enum Direction {
     North,
     South,
@@ -0,0 +1,421 @@
+use std::collections::BTreeSet;
+
+use super::Move;
+use crate::board::{Board, Coord};
+use crate::piece::{Color, Piece};
+
+/// Color of the piece in `from`, if any
+fn color_at(coord: &Coord, board: &Board) -> Option<Color> {
+    match board.get_piece(coord) {
+        Ok(Some(piece)) => Some(piece.color),
+        _ => None,
+    }
+}
+
+/// True if `to` holds a piece of the other color than the one in `from`
+fn is_capture(from: &Coord, to: &Coord, board: &Board) -> bool {
+    match (color_at(from, board), color_at(to, board)) {
+        (Some(color), Some(target)) => color != target,
+        _ => false,
+    }
+}
+
+/// Wraps a move so it can only capture (Betza `c`). With `en_passant`, it can
+/// also go to the en passant cell (Betza `e`), as pawns do.
+pub struct CaptureOnly<M: Move> {
+    inner: M,
+    en_passant: bool,
+}
+
+impl<M: Move> CaptureOnly<M> {
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            en_passant: false,
+        }
+    }
+
+    pub fn with_en_passant(inner: M) -> Self {
+        Self {
+            inner,
+            en_passant: true,
+        }
+    }
+
+    fn captures(&self, from: &Coord, to: &Coord, board: &Board) -> bool {
+        is_capture(from, to, board) || (self.en_passant && board.info.en_passant == Some(*to))
+    }
+}
+
+impl<M: Move> Move for CaptureOnly<M> {
+    fn is_move_valid(&self, from: Coord, to: Coord, board: &Board) -> bool {
+        self.captures(&from, &to, board) && self.inner.is_move_valid(from, to, board)
+    }
+
+    fn allowed_moves(&self, from: Coord, board: &Board) -> Vec<Coord> {
+        self.inner
+            .allowed_moves(from, board)
+            .into_iter()
+            .filter(|to| self.captures(&from, to, board))
+            .collect()
+    }
+
+    fn attacks(&self, from: Coord, to: Coord, board: &Board) -> bool {
+        self.inner.attacks(from, to, board)
+    }
+
+    fn move_piece(&self, from: Coord, to: Coord, board: &mut Board) {
+        self.inner.move_piece(from, to, board)
+    }
+
+    fn can_promote(&self, piece: &Piece, prom_coord: &Coord, board: &Board) -> bool {
+        self.inner.can_promote(piece, prom_coord, board)
+    }
+}
+
+/// Wraps a move so it can only go to empty cells (Betza `m`)
+pub struct NoCapture<M: Move> {
+    inner: M,
+}
+
+impl<M: Move> NoCapture<M> {
+    pub fn new(inner: M) -> Self {
+        Self { inner }
+    }
+}
+
+impl<M: Move> Move for NoCapture<M> {
+    fn is_move_valid(&self, from: Coord, to: Coord, board: &Board) -> bool {
+        matches!(board.get_piece(&to), Ok(None)) && self.inner.is_move_valid(from, to, board)
+    }
+
+    fn allowed_moves(&self, from: Coord, board: &Board) -> Vec<Coord> {
+        self.inner
+            .allowed_moves(from, board)
+            .into_iter()
+            .filter(|to| matches!(board.get_piece(to), Ok(None)))
+            .collect()
+    }
+
+    fn attacks(&self, _from: Coord, _to: Coord, _board: &Board) -> bool {
+        false
+    }
+
+    fn move_piece(&self, from: Coord, to: Coord, board: &mut Board) {
+        self.inner.move_piece(from, to, board)
+    }
+
+    fn can_promote(&self, piece: &Piece, prom_coord: &Coord, board: &Board) -> bool {
+        self.inner.can_promote(piece, prom_coord, board)
+    }
+}
+
+/// Keeps the moves of `inner` that go in one of `directions`, relative to the
+/// piece's color: forward is towards the other side. Each direction is a Betza
+/// direction modifier (`f`, `b`, `l`, `r`, `s`, `v`) or a compound of two that
+/// must match both (`fl`, `fs`, `fh`...). A doubled letter (`ff`) keeps the
+/// narrow half, e.g. the two knight jumps that go 2 cells forward.
+pub struct Directions<M: Move> {
+    inner: M,
+    directions: Vec<String>,
+}
+
+impl<M: Move> Directions<M> {
+    pub fn new(inner: M, directions: Vec<String>) -> Self {
+        Self { inner, directions }
+    }
+
+    fn allows(&self, from: &Coord, to: &Coord, board: &Board) -> bool {
+        let (forward, right) = match color_at(from, board) {
+            Some(Color::White) => (from.row - to.row, to.col - from.col),
+            Some(Color::Black) => (to.row - from.row, from.col - to.col),
+            None => return false,
+        };
+
+        self.directions
+            .iter()
+            .any(|direction| matches_direction(direction, forward, right))
+    }
+}
+
+/// True if a step of `forward` rows and `right` columns matches a direction
+fn matches_direction(direction: &str, forward: i32, right: i32) -> bool {
+    let mut letters = direction.chars();
+    let doubled = matches!((letters.next(), letters.next()), (Some(a), Some(b)) if a == b);
+    let vertical = forward.abs() > right.abs();
+    let sideways = right.abs() > forward.abs();
+
+    direction.chars().all(|c| match c {
+        'f' => forward > 0 && (!doubled || vertical),
+        'b' => forward < 0 && (!doubled || vertical),
+        'l' => right < 0 && (!doubled || sideways),
+        'r' => right > 0 && (!doubled || sideways),
+        's' => sideways,
+        'v' => vertical,
+        'h' => true, // the half given by the other letter
+        _ => false,
+    })
+}
+
+impl<M: Move> Move for Directions<M> {
+    fn is_move_valid(&self, from: Coord, to: Coord, board: &Board) -> bool {
+        self.allows(&from, &to, board) && self.inner.is_move_valid(from, to, board)
+    }
+
+    fn allowed_moves(&self, from: Coord, board: &Board) -> Vec<Coord> {
+        self.inner
+            .allowed_moves(from, board)
+            .into_iter()
+            .filter(|to| self.allows(&from, to, board))
+            .collect()
+    }
+
+    fn attacks(&self, from: Coord, to: Coord, board: &Board) -> bool {
+        self.allows(&from, &to, board) && self.inner.attacks(from, to, board)
+    }
+
+    fn move_piece(&self, from: Coord, to: Coord, board: &mut Board) {
+        self.inner.move_piece(from, to, board)
+    }
+
+    fn can_promote(&self, piece: &Piece, prom_coord: &Coord, board: &Board) -> bool {
+        self.inner.can_promote(piece, prom_coord, board)
+    }
+}
+
+/// Wraps a move so it can only be played from the piece's starting ranks
+/// (Betza `i`). Pieces don't remember if they moved, so a piece that comes
+/// back to one of those ranks can play it again, as pawns do.
+pub struct FirstMoveOnly<M: Move> {
+    inner: M,
+    /// Ranks counted from the piece's side, see `Board::relative_rank`.
+    /// `None` uses `BoardInfo::double_step_ranks`, as pawns do.
+    ranks: Option<Vec<i32>>,
+}
+
+impl<M: Move> FirstMoveOnly<M> {
+    pub fn new(inner: M) -> Self {
+        Self { inner, ranks: None }
+    }
+
+    pub fn on_ranks(inner: M, ranks: Vec<i32>) -> Self {
+        Self {
+            inner,
+            ranks: Some(ranks),
+        }
+    }
+
+    fn is_first_move(&self, from: &Coord, board: &Board) -> bool {
+        let color = match color_at(from, board) {
+            Some(color) => color,
+            None => return false,
+        };
+
+        match &self.ranks {
+            Some(ranks) => ranks.contains(&board.relative_rank(from.row, color)),
+            None => board.is_pawn_row(from.row, color),
+        }
+    }
+}
+
+impl<M: Move> Move for FirstMoveOnly<M> {
+    fn is_move_valid(&self, from: Coord, to: Coord, board: &Board) -> bool {
+        self.is_first_move(&from, board) && self.inner.is_move_valid(from, to, board)
+    }
+
+    fn allowed_moves(&self, from: Coord, board: &Board) -> Vec<Coord> {
+        match self.is_first_move(&from, board) {
+            true => self.inner.allowed_moves(from, board),
+            false => vec![],
+        }
+    }
+
+    fn attacks(&self, from: Coord, to: Coord, board: &Board) -> bool {
+        self.is_first_move(&from, board) && self.inner.attacks(from, to, board)
+    }
+
+    fn move_piece(&self, from: Coord, to: Coord, board: &mut Board) {
+        self.inner.move_piece(from, to, board)
+    }
+
+    fn can_promote(&self, piece: &Piece, prom_coord: &Coord, board: &Board) -> bool {
+        self.inner.can_promote(piece, prom_coord, board)
+    }
+}
+
+/// Repeats each step of a move in the same direction up to `times` times
+/// (unlimited if `None`) through empty cells, e.g. a knight rider (Betza
+/// `NN`) or a rook made of `W` steps
+pub struct Repeat<M: Move> {
+    inner: M,
+    times: Option<u32>,
+}
+
+impl<M: Move> Repeat<M> {
+    pub fn new(inner: M, times: Option<u32>) -> Self {
+        Self { inner, times }
+    }
+}
+
+impl<M: Move> Move for Repeat<M> {
+    fn is_move_valid(&self, from: Coord, to: Coord, board: &Board) -> bool {
+        self.allowed_moves(from, board).contains(&to)
+    }
+
+    fn allowed_moves(&self, from: Coord, board: &Board) -> Vec<Coord> {
+        let color = match color_at(&from, board) {
+            Some(color) => color,
+            None => return vec![],
+        };
+
+        let mut moves = BTreeSet::new();
+        for first in self.inner.allowed_moves(from, board) {
+            let step = Coord {
+                row: first.row - from.row,
+                col: first.col - from.col,
+            };
+            let mut current = first;
+
+            for n in 1.. {
+                match board.get_piece(&current) {
+                    Ok(None) => {
+                        moves.insert(current);
+                    }
+                    Ok(Some(piece)) => {
+                        if piece.color != color {
+                            moves.insert(current);
+                        }
+                        break;
+                    }
+                    Err(_) => break,
+                }
+
+                if self.times.is_some_and(|times| n >= times) {
+                    break;
+                }
+                current = current + step;
+            }
+        }
+
+        moves.into_iter().collect()
+    }
+
+    fn move_piece(&self, from: Coord, to: Coord, board: &mut Board) {
+        self.inner.move_piece(from, to, board)
+    }
+
+    fn can_promote(&self, piece: &Piece, prom_coord: &Coord, board: &Board) -> bool {
+        self.inner.can_promote(piece, prom_coord, board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moves::jump::Jump;
+    use crate::moves::Line;
+    use crate::piece::PieceType;
+    use std::sync::Arc;
+
+    fn board_with(moves: Vec<Arc<dyn Move>>, fen: &str, from: Coord) -> Board {
+        let mut board = Board::from_fen(fen).unwrap();
+        board.set_piece(Piece::new(
+            Color::White,
+            PieceType::Custom('x'),
+            moves,
+            from,
+        ));
+        board
+    }
+
+    #[test]
+    fn test_capture_filters() {
+        // d4 with an enemy pawn on d6 and a friendly one on f4
+        let d4 = Coord::from_rank_file(3, 3, 8);
+        let fen = "4k3/8/3p4/8/5P2/8/8/4K3 w - - 0 1";
+
+        let board = board_with(vec![Arc::new(CaptureOnly::new(Line::new(None)))], fen, d4);
+        assert_eq!(
+            board.get_moves(&d4, false),
+            [Coord::from_rank_file(5, 3, 8)]
+        );
+
+        let board = board_with(vec![Arc::new(NoCapture::new(Line::new(None)))], fen, d4);
+        assert_eq!(board.get_moves(&d4, false).len(), 8);
+        assert!(!board.is_square_attacked(&Coord::from_rank_file(5, 3, 8), Color::White));
+    }
+
+    #[test]
+    fn test_directions() {
+        let d4 = Coord::from_rank_file(3, 3, 8);
+        let fen = "4k3/8/8/8/8/8/8/4K3 w - - 0 1";
+        let moves = |directions: &[&str]| -> Vec<Arc<dyn Move>> {
+            let directions = directions.iter().map(|d| d.to_string()).collect();
+            vec![Arc::new(Directions::new(Jump::new(), directions))]
+        };
+
+        let n_moves =
+            |directions: &[&str]| board_with(moves(directions), fen, d4).get_moves(&d4, false);
+        assert_eq!(n_moves(&["f"]).len(), 4);
+        assert_eq!(
+            n_moves(&["ff"]),
+            [
+                Coord::from_rank_file(5, 2, 8),
+                Coord::from_rank_file(5, 4, 8)
+            ]
+        );
+        assert_eq!(n_moves(&["fs"]).len(), 2);
+        assert_eq!(n_moves(&["fh", "bb"]).len(), 6);
+        assert_eq!(n_moves(&["fr"]).len(), 2);
+
+        // Forward is relative to the color
+        let d5 = Coord::from_rank_file(4, 3, 8);
+        let mut board = Board::from_fen(fen).unwrap();
+        let line = Directions::new(Line::new(Some(1)), vec!["f".to_string()]);
+        board.set_piece(Piece::new(
+            Color::Black,
+            PieceType::Custom('x'),
+            vec![Arc::new(line)],
+            d5,
+        ));
+        assert_eq!(
+            board.get_moves(&d5, true),
+            [Coord::from_rank_file(3, 3, 8)]
+        );
+    }
+
+    #[test]
+    fn test_first_move_only() {
+        let fen = "4k3/8/8/8/8/8/8/4K3 w - - 0 1";
+        let moves = || -> Vec<Arc<dyn Move>> { vec![Arc::new(FirstMoveOnly::new(Jump::new()))] };
+
+        let b2 = Coord::from_rank_file(1, 1, 8);
+        assert_eq!(board_with(moves(), fen, b2).get_moves(&b2, false).len(), 4);
+        let b3 = Coord::from_rank_file(2, 1, 8);
+        assert!(board_with(moves(), fen, b3)
+            .get_moves(&b3, false)
+            .is_empty());
+
+        let b1 = Coord::from_rank_file(0, 1, 8);
+        let moves: Vec<Arc<dyn Move>> =
+            vec![Arc::new(FirstMoveOnly::on_ranks(Jump::new(), vec![0]))];
+        assert_eq!(board_with(moves, fen, b1).get_moves(&b1, false).len(), 3);
+    }
+
+    #[test]
+    fn test_repeat() {
+        // a1 knight rider: b3, c5 and d7 in one direction, the c2 pawn
+        // blocks the other one
+        let a1 = Coord::from_rank_file(0, 0, 8);
+        let fen = "4k3/8/8/8/8/8/2P5/4K3 w - - 0 1";
+
+        let board = board_with(vec![Arc::new(Repeat::new(Jump::new(), None))], fen, a1);
+        let moves = board.get_moves(&a1, false);
+        assert!(moves.contains(&Coord::from_rank_file(6, 3, 8)));
+        assert!(!moves.contains(&Coord::from_rank_file(1, 2, 8)));
+        assert_eq!(moves.len(), 3);
+
+        let board = board_with(vec![Arc::new(Repeat::new(Jump::new(), Some(1)))], fen, a1);
+        assert_eq!(board.get_moves(&a1, false).len(), 1);
+    }
+}
@@ -1,151 +1,55 @@
-use super::{Direction, Move};
+use std::collections::BTreeSet;
+
+use super::{CaptureOnly, Diagonal, Directions, FirstMoveOnly, Line, Move, NoCapture};
 use crate::board::{Board, Coord};
-use crate::moves::parse_direction;
-use crate::piece::{Color, Piece};
 
-pub struct PawnMove {}
+/// The pawn, composed from the move modifiers (Betza `fmWfceFifmW2`): one
+/// step forward to an empty cell, two from the starting ranks through empty
+/// cells, and forward diagonal captures, en passant included
+pub struct PawnMove {
+    moves: Vec<Box<dyn Move>>,
+}
 
 impl PawnMove {
     pub fn new() -> PawnMove {
-        PawnMove {}
-    }
-
-    fn check_one_forward_step(&self, from: &Coord, to: &Coord, board: &Board) -> bool {
-        if to.col != from.col {
-            return false;
-        }
-
-        match board.get_piece(to) {
-            Ok(Some(_)) => false,
-            Ok(None) => true,
-            _ => unreachable!(), // outside of board
-        }
-    }
-
-    fn check_two_forward_steps(&self, from_piece: &Piece, step: &Coord, board: &Board) -> bool {
-        if !board.is_pawn_row(from_piece.coord.row, from_piece.color) {
-            return false;
-        }
-
-        for _ in 0..2 {
-            let next_coord = from_piece.coord.clone() + step.clone();
-            if !self.check_one_forward_step(&from_piece.coord, &next_coord, &board) {
-                return false;
-            }
-        }
-
-        true
-    }
-
-    fn check_en_passant(&self, to: &Coord, board: &Board) -> bool {
-        match board.info.en_passant {
-            Some(coord) => &coord == to,
-            None => false,
+        let forward = || vec!["f".to_string()];
+
+        PawnMove {
+            moves: vec![
+                Box::new(NoCapture::new(Directions::new(
+                    Line::new(Some(1)),
+                    forward(),
+                ))),
+                Box::new(FirstMoveOnly::new(NoCapture::new(Directions::new(
+                    Line::new(Some(2)),
+                    forward(),
+                )))),
+                Box::new(CaptureOnly::with_en_passant(Directions::new(
+                    Diagonal::new(Some(1)),
+                    forward(),
+                ))),
+            ],
         }
     }
-
-    fn check_capture(&self, from_piece: &Piece, to: &Coord, board: &Board) -> bool {
-        let to_piece = match board.get_piece(to) {
-            Ok(Some(piece)) => piece,
-            Ok(None) => return self.check_en_passant(&to, &board), // Empty cell
-            _ => return false,
-        };
-        to_piece.color != from_piece.color
-    }
 }
 
 impl Move for PawnMove {
     fn is_move_valid(&self, from: Coord, to: Coord, board: &Board) -> bool {
-        let from_piece = match board.get_piece(&from) {
-            Ok(Some(piece)) => piece,
-            _ => return false,
-        };
-
-        let direction = match parse_direction(&from, &to) {
-            Ok(dir) => dir,
-            Err(_) => return false, // Invalid direction
-        };
-
-        let direction = match from_piece.color {
-            Color::Black => match direction {
-                Direction::South | Direction::SouthEast | Direction::SouthWest => direction,
-                _ => return false,
-            },
-            Color::White => match direction {
-                Direction::North | Direction::NorthEast | Direction::NorthWest => direction,
-                _ => return false,
-            },
-        };
-
-        let row_dis = (from.row as i32 - to.row as i32).abs();
-        let col_dis = (from.col as i32 - to.col as i32).abs();
-
-        if col_dis == 1 && row_dis == 1 {
-            return self.check_capture(&from_piece, &to, &board);
-        }
-
-        if col_dis != 0 {
-            return false; // Can't capture
-        }
-
-        if row_dis == 1 {
-            return self.check_one_forward_step(&from, &to, &board);
-        } else if row_dis == 2 {
-            return self.check_two_forward_steps(&from_piece, &direction.get_step(), &board);
-        }
-        false
+        self.moves.iter().any(|m| m.is_move_valid(from, to, board))
     }
 
     fn allowed_moves(&self, from: Coord, board: &Board) -> Vec<Coord> {
-        let from_piece = match board.get_piece(&from) {
-            Ok(Some(piece)) => piece,
-            _ => return vec![],
-        };
-
-        let legal_directions = match from_piece.color {
-            Color::Black => [Direction::South, Direction::SouthEast, Direction::SouthWest],
-            Color::White => [Direction::North, Direction::NorthEast, Direction::NorthWest],
-        };
-
-        let passant_cell = board.info.en_passant;
-
-        let mut moves = vec![];
-
-        for direction in legal_directions.iter() {
-            let step = direction.get_step();
-            let next_coord = from_piece.coord.clone() + step.clone();
-
-            if !board.in_bounds(&next_coord) {
-                continue;
-            }
-
-            match direction {
-                Direction::North | Direction::South => {
-                    if self.check_one_forward_step(&from, &next_coord, &board) {
-                        moves.push(next_coord.clone());
-                    }
-                    // can walk twice
-                    if self.check_two_forward_steps(&from_piece, &step, &board) {
-                        // +1 +1
-                        moves.push(next_coord.clone() + step.clone());
-                    }
-                }
-                // NE, NW, SE, SW
-                _ => {
-                    if self.check_capture(&from_piece, &next_coord, &board) {
-                        moves.push(next_coord.clone());
-                    }
-                }
-            }
-
-            if let Some(coord) = passant_cell {
-                if coord == next_coord {
-                    moves.push(next_coord.clone());
-                }
-            }
-        }
+        // The single step is also a double step from the starting ranks
+        let moves: BTreeSet<Coord> = self
+            .moves
+            .iter()
+            .flat_map(|m| m.allowed_moves(from, board))
+            .collect();
+        moves.into_iter().collect()
+    }
 
-        moves
+    fn attacks(&self, from: Coord, to: Coord, board: &Board) -> bool {
+        self.moves.iter().any(|m| m.attacks(from, to, board))
     }
 }
 
@@ -250,4 +154,18 @@ mod tests {
         assert!(pawn.is_move_valid(from, Coord { row: 3, col: 0 }, &board));
         assert!(pawn.is_move_valid(from, Coord { row: 2, col: 0 }, &board));
     }
+
+    #[test]
+    pub fn test_double_step_blocked() {
+        // a knight in e4 blocks e2-e4 but not e2-e3
+        let board = Board::from_fen("4k3/8/8/8/4n3/8/4P3/4K3 w - - 0 1").unwrap();
+        let pawn = PawnMove::new();
+        let from = Coord::from_rank_file(1, 4, 8);
+
+        assert_eq!(
+            pawn.allowed_moves(from, &board),
+            [Coord::from_rank_file(2, 4, 8)]
+        );
+        assert!(!pawn.is_move_valid(from, Coord::from_rank_file(3, 4, 8), &board));
+    }
 }
@@ -1,50 +1,153 @@
 use crate::{
-    board::{Board, Coord},
+    board::{Bitboard, Board, Coord},
     piece::Piece,
 };
 
 use super::Direction;
 
-pub fn can_traverse(
-    board: &Board,
-    from_piece: &Piece,
-    to: &Coord,
-    step: &Coord,
-    max_range: u32,
-) -> bool {
-    let mut current_coord = from_piece.coord.clone();
+const SIZE: i32 = 8;
 
-    for _ in 0..max_range {
-        let next_coord = current_coord.clone() + step.clone();
+/// Unit step from `a` towards `b` if they are different cells on the same
+/// row, column or diagonal
+const fn line_step(a: (i32, i32), b: (i32, i32)) -> Option<(i32, i32)> {
+    let (dr, dc) = (b.0 - a.0, b.1 - a.1);
+    if (dr == 0 && dc == 0) || (dr != 0 && dc != 0 && dr.abs() != dc.abs()) {
+        return None;
+    }
+    Some((dr.signum(), dc.signum()))
+}
 
-        // Get the next cell
-        let next_piece = match board.get_piece(&next_coord) {
-            Ok(piece) => piece,
-            Err(_) => return false, // out of bounds -> false
-        };
+const fn in_size(row: i32, col: i32) -> bool {
+    row >= 0 && row < SIZE && col >= 0 && col < SIZE
+}
+
+/// Ray tables of an 8x8 board, built at compile time: cells strictly between
+/// each pair of cells (`lines == false`) or the whole line through them
+const fn ray_table(lines: bool) -> [[Bitboard; 64]; 64] {
+    let mut table = [[Bitboard::EMPTY; 64]; 64];
+
+    let mut a = 0;
+    while a < 64 {
+        let mut b = 0;
+        while b < 64 {
+            let from = (a as i32 / SIZE, a as i32 % SIZE);
+            let to = (b as i32 / SIZE, b as i32 % SIZE);
+
+            if let Some((dr, dc)) = line_step(from, to) {
+                let mut bits = 0u64;
+                // whole lines start at the edge of the board behind `from`
+                let (mut row, mut col) = (from.0, from.1);
+                if lines {
+                    while in_size(row - dr, col - dc) {
+                        row -= dr;
+                        col -= dc;
+                    }
+                } else {
+                    row += dr;
+                    col += dc;
+                }
 
-        // if the target cell
-        if &next_coord == to {
-            match board.get_piece(&next_coord) {
-                Ok(Some(piece)) => {
-                    // if the same color -> invalid
-                    return piece.color != from_piece.color;
+                while in_size(row, col) && (lines || row != to.0 || col != to.1) {
+                    bits |= 1 << (row * SIZE + col);
+                    row += dr;
+                    col += dc;
                 }
-                Ok(None) => return true, // empty cell -> valid
-                _ => return false,       // Out of bounds -> should have been caught before
+                table[a][b] = Bitboard(bits);
             }
+            b += 1;
+        }
+        a += 1;
+    }
+
+    table
+}
+
+static BETWEEN: [[Bitboard; 64]; 64] = ray_table(false);
+static LINES: [[Bitboard; 64]; 64] = ray_table(true);
+
+/// Indexes of both cells in the 8x8 tables, if they fit in them
+fn table_indexes(a: &Coord, b: &Coord) -> Option<(usize, usize)> {
+    match (Bitboard::index(a), Bitboard::index(b)) {
+        (Some(a), Some(b)) => Some((a as usize, b as usize)),
+        _ => None,
+    }
+}
+
+/// Cells from `from` (excluded) towards `direction`, without end: callers
+/// stop at the first cell out of the board
+pub fn direction_iter(from: &Coord, direction: &Direction) -> impl Iterator<Item = Coord> {
+    let step = direction.get_step();
+    std::iter::successors(Some(*from + step), move |coord| Some(*coord + step))
+}
+
+/// Cells strictly between `a` and `b`, from `a` to `b`. Empty if they are not
+/// on the same row, column or diagonal, or are next to each other.
+pub fn ray_between(a: &Coord, b: &Coord) -> Vec<Coord> {
+    if let Some((i, j)) = table_indexes(a, b) {
+        let mut ray: Vec<Coord> = BETWEEN[i][j].iter().collect();
+        if i > j {
+            ray.reverse();
         }
+        return ray;
+    }
+
+    let (dr, dc) = match line_step((a.row, a.col), (b.row, b.col)) {
+        Some(step) => step,
+        None => return vec![],
+    };
+    let step = Coord { row: dr, col: dc };
+    std::iter::successors(Some(*a + step), move |coord| Some(*coord + step))
+        .take_while(|coord| coord != b)
+        .collect()
+}
+
+/// True if the three cells are on the same row, column or diagonal
+pub fn are_aligned(a: &Coord, b: &Coord, c: &Coord) -> bool {
+    if a == b {
+        return a == c || line_step((a.row, a.col), (c.row, c.col)).is_some();
+    }
+
+    if let Some((i, j)) = table_indexes(a, b) {
+        return LINES[i][j].contains(c);
+    }
 
-        // if there is a piece in the way -> invalid
-        if !next_piece.is_none() {
-            return false;
+    match line_step((a.row, a.col), (b.row, b.col)) {
+        Some(step) => {
+            c == a
+                || line_step((a.row, a.col), (c.row, c.col))
+                    .is_some_and(|other| other == step || other == (-step.0, -step.1))
         }
+        None => false,
+    }
+}
+
+/// True if the piece can slide to `to`, through empty cells and at most
+/// `max_range` cells away, and `to` is empty or an enemy piece
+pub fn can_traverse(board: &Board, from_piece: &Piece, to: &Coord, max_range: u32) -> bool {
+    let from = from_piece.coord;
+    if line_step((from.row, from.col), (to.row, to.col)).is_none() {
+        return false;
+    }
+
+    let distance = std::cmp::max((to.row - from.row).abs(), (to.col - from.col).abs());
+    if distance as u32 > max_range {
+        return false;
+    }
 
-        current_coord = next_coord;
+    // if there is a piece in the way -> invalid
+    let path = ray_between(&from, to);
+    if path
+        .iter()
+        .any(|coord| !matches!(board.get_piece(coord), Ok(None)))
+    {
+        return false;
     }
 
-    // couldn't reach target cell in the given direction
-    false
+    match board.get_piece(to) {
+        Ok(Some(piece)) => piece.color != from_piece.color, // if the same color -> invalid
+        Ok(None) => true,                                   // empty cell -> valid
+        Err(_) => false,                                    // out of bounds
+    }
 }
 
 pub fn legal_coords_along_direction(
@@ -54,17 +157,13 @@ pub fn legal_coords_along_direction(
     from_piece: &Piece,
     max_range: u32,
 ) -> Vec<Coord> {
-    let step = direction.get_step();
-    let mut current_coord = from.clone();
     let mut legal_coords = vec![];
     // for each coord in the direction
-    for _ in 0..max_range {
-        let next_coord = current_coord.clone() + step.clone();
-
+    for next_coord in direction_iter(from, direction).take(max_range as usize) {
         // Get the next cell
         let next_piece = match board.get_piece(&next_coord) {
             Ok(piece) => piece,
-            Err(_) => break, // out of bounds -> false
+            Err(_) => break, // out of bounds
         };
 
         match next_piece {
@@ -77,7 +176,62 @@ pub fn legal_coords_along_direction(
                 break; // Break -> There is a piece blocking the way (friendly & enemy)
             }
         }
-        current_coord = next_coord;
     }
     legal_coords
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ray_between() {
+        let (a1, h8, d4) = (
+            Coord::from_rank_file(0, 0, 8),
+            Coord::from_rank_file(7, 7, 8),
+            Coord::from_rank_file(3, 3, 8),
+        );
+        let ray = ray_between(&a1, &h8);
+        assert_eq!(ray.len(), 6);
+        assert_eq!(ray[0], Coord::from_rank_file(1, 1, 8));
+        assert_eq!(ray_between(&h8, &a1)[0], Coord::from_rank_file(6, 6, 8));
+        assert!(ray_between(&a1, &Coord::from_rank_file(1, 2, 8)).is_empty());
+        assert!(ray_between(&d4, &Coord::from_rank_file(4, 4, 8)).is_empty());
+
+        // same result outside the tables
+        let (far, farther) = (Coord { row: 9, col: 0 }, Coord { row: 9, col: 3 });
+        assert_eq!(
+            ray_between(&far, &farther),
+            [Coord { row: 9, col: 1 }, Coord { row: 9, col: 2 }]
+        );
+    }
+
+    #[test]
+    fn test_are_aligned() {
+        let (a1, d4, h8, h1) = (
+            Coord::from_rank_file(0, 0, 8),
+            Coord::from_rank_file(3, 3, 8),
+            Coord::from_rank_file(7, 7, 8),
+            Coord::from_rank_file(0, 7, 8),
+        );
+        assert!(are_aligned(&d4, &h8, &a1));
+        assert!(are_aligned(&a1, &h1, &Coord::from_rank_file(0, 3, 8)));
+        assert!(!are_aligned(&a1, &d4, &h1));
+        assert!(are_aligned(&a1, &a1, &h8));
+
+        let (far, farther) = (Coord { row: 9, col: 0 }, Coord { row: 8, col: 1 });
+        assert!(are_aligned(&far, &farther, &Coord { row: 7, col: 2 }));
+        assert!(!are_aligned(&far, &farther, &Coord { row: 7, col: 3 }));
+    }
+
+    #[test]
+    fn test_direction_iter() {
+        let cells: Vec<Coord> = direction_iter(&Coord { row: 1, col: 1 }, &Direction::NorthWest)
+            .take(2)
+            .collect();
+        assert_eq!(
+            cells,
+            [Coord { row: 0, col: 0 }, Coord { row: -1, col: -1 }]
+        );
+    }
+}
@@ -11,39 +11,78 @@ pub enum AlgebraicNotationError {
     InvalidCell(String),
 }
 
+/// Files are named like spreadsheet columns: a..z, then aa, ab, ..., zz, aaa...
+pub fn file_to_str(col: u32) -> String {
+    let mut name = vec![];
+    let mut n = col + 1;
+    while n > 0 {
+        n -= 1;
+        name.push((b'a' + (n % 26) as u8) as char);
+        n /= 26;
+    }
+    name.iter().rev().collect()
+}
+
+/// Inverse of `file_to_str`
+pub fn file_from_str(file: &str) -> Option<u32> {
+    if file.is_empty() || !file.chars().all(|c| c.is_ascii_lowercase()) {
+        return None;
+    }
+
+    let mut n: u32 = 0;
+    for c in file.chars() {
+        n = n.checked_mul(26)?.checked_add(c as u32 - 'a' as u32 + 1)?;
+    }
+    Some(n - 1)
+}
+
 impl AlgebraicNotation {
+    /// Parses a cell as its file letters followed by its rank number, e.g.
+    /// `e4`, `a10` or `aa3` (boards with more than 26 files)
     pub fn cell_from_str(&self, cell: &str) -> Result<Coord, AlgebraicNotationError> {
-        if cell.len() != 2 {
-            return Err(AlgebraicNotationError::InvalidString(
-                "Invalid cell".to_string(),
-            ));
-        }
-
-        let mut chars = cell.chars();
-        let (col, row) = (chars.next(), chars.next());
+        let split = cell
+            .find(|c: char| !c.is_ascii_lowercase())
+            .unwrap_or(cell.len());
+        let (file, rank) = cell.split_at(split);
 
-        if col.is_none() || row.is_none() {
-            return Err(AlgebraicNotationError::InvalidCell(
+        if file.is_empty() || rank.is_empty() || !rank.chars().all(|c| c.is_ascii_digit()) {
+            return Err(AlgebraicNotationError::InvalidString(
                 "Invalid cell".to_string(),
             ));
         }
 
-        // notation 8 -> board row 0
-        // Notation a -> board col 0
-
-        let col = col.unwrap() as u32 - 'a' as u32;
-        let row = row.unwrap() as u32 - '1' as u32;
+        let col = file_from_str(file);
+        let rank = rank
+            .parse::<u32>()
+            .ok()
+            .and_then(|rank| rank.checked_sub(1));
+
+        let (col, rank) = match (col, rank) {
+            (Some(col), Some(rank)) if col < self.cols && rank < self.rows => (col, rank),
+            _ => {
+                return Err(AlgebraicNotationError::InvalidCell(
+                    "Invalid cell".to_string(),
+                ))
+            }
+        };
+
+        Ok(Coord::from_rank_file(rank as i32, col as i32, self.rows))
+    }
 
-        if col >= self.cols || row >= self.rows {
+    /// Inverse of `cell_from_str`
+    pub fn cell_to_str(&self, coord: &Coord) -> Result<String, AlgebraicNotationError> {
+        if coord.row < 0
+            || coord.col < 0
+            || coord.row >= self.rows as i32
+            || coord.col >= self.cols as i32
+        {
             return Err(AlgebraicNotationError::InvalidCell(
                 "Invalid cell".to_string(),
             ));
         }
 
-        Ok(Coord {
-            row: ((row as i32) - (self.rows as i32 - 1)).abs(),
-            col: col as i32,
-        })
+        let (rank, file) = coord.to_rank_file(self.rows);
+        Ok(format!("{}{}", file_to_str(file as u32), rank + 1))
     }
 }
 
@@ -83,11 +122,18 @@ mod tests {
             ))
         );
         assert_eq!(
-            algebraic_notation.cell_from_str("a12"),
+            algebraic_notation.cell_from_str("1a"),
             Err(AlgebraicNotationError::InvalidString(
                 "Invalid cell".to_string()
             ))
         );
+        // valid, but out of the board
+        assert_eq!(
+            algebraic_notation.cell_from_str("a12"),
+            Err(AlgebraicNotationError::InvalidCell(
+                "Invalid cell".to_string()
+            ))
+        );
     }
 
     #[test]
@@ -107,6 +153,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cell_to_str() {
+        let algebraic_notation = AlgebraicNotation { rows: 8, cols: 8 };
+        for cell in ["a1", "h8", "a8", "h1", "e4"] {
+            let coord = algebraic_notation.cell_from_str(cell).unwrap();
+            assert_eq!(algebraic_notation.cell_to_str(&coord).unwrap(), cell);
+        }
+
+        assert!(algebraic_notation
+            .cell_to_str(&Coord { row: 8, col: 0 })
+            .is_err());
+    }
+
+    #[test]
+    fn test_large_boards() {
+        let algebraic_notation = AlgebraicNotation { rows: 12, cols: 30 };
+
+        assert_eq!(
+            algebraic_notation.cell_from_str("a10").unwrap(),
+            Coord { row: 2, col: 0 }
+        );
+        assert_eq!(
+            algebraic_notation.cell_from_str("ad1").unwrap(),
+            Coord { row: 11, col: 29 }
+        );
+        for cell in ["a1", "z12", "aa7", "ad10"] {
+            let coord = algebraic_notation.cell_from_str(cell).unwrap();
+            assert_eq!(algebraic_notation.cell_to_str(&coord).unwrap(), cell);
+        }
+
+        assert_eq!(file_to_str(25), "z");
+        assert_eq!(file_to_str(26), "aa");
+        assert_eq!(file_from_str("zz"), Some(701));
+        assert_eq!(file_from_str("aaa"), Some(702));
+        assert!(algebraic_notation.cell_from_str("a0").is_err());
+    }
+
     #[test]
     fn test_row_equivalence() {
         let black_king = "e8";
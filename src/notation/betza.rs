@@ -0,0 +1,338 @@
+use std::iter::Peekable;
+use std::str::Chars;
+use std::sync::Arc;
+
+use crate::moves::jump::Jump;
+use crate::moves::{
+    CaptureOnly, Diagonal, Directions, FirstMoveOnly, Line, Move, NoCapture, Repeat,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum BetzaError {
+    InvalidAtom(String),
+    UnsupportedModifier(String),
+    InvalidRange(String),
+}
+
+/// Parse function for *Betza* (funny) notation
+///
+/// Compiles a piece description into the moves of that piece, e.g. `"N"` is a
+/// knight, `"WF"` a king without castling, `"R4"` a rook limited to 4 cells.
+///
+/// Supported atoms:
+/// * Sliders: `W` (1 orthogonal), `F` (1 diagonal), `R`, `B`, `Q`, `K`.
+///   `WW` and `FF` are riders (same as `R` and `B`).
+/// * Leapers: `N` (2, 1), `D` (2, 0), `A` (2, 2), `H` (3, 0), `C` (3, 1),
+///   `Z` (3, 2), `G` (3, 3).
+///
+/// Every atom accepts a maximum range suffix (`R2`, `W3`, `N2`): `W`, `F` and
+/// `K` slide up to that many cells and leapers become riders. Doubled leapers
+/// are riders too (`NN`, `NN2`).
+///
+/// Supported modifiers, before the atom: `c` (capture only), `m` (move
+/// without capturing only), `e` (capture en passant, implies `c`) and `i`
+/// (only from the starting ranks, see `FirstMoveOnly`), e.g. `mWcF` moves
+/// orthogonally and captures diagonally.
+///
+/// Direction modifiers are relative to the piece's color: `f`, `b`, `l`, `r`,
+/// `s` (sideways) and `v` (vertical), or compounds such as `fl`, `fs`, `ff`
+/// and `fh` (see `Directions`). A pawn is `fmWfceF`, and `ifmW2` adds its
+/// double step.
+///
+/// https://www.gnu.org/software/xboard/Betza.html
+pub fn parse(betza: &str) -> Result<Vec<Arc<dyn Move>>, BetzaError> {
+    let mut moves: Vec<Arc<dyn Move>> = vec![];
+    let mut chars = betza.chars().peekable();
+
+    let mut modifiers = vec![];
+
+    while let Some(c) = chars.next() {
+        if c.is_ascii_lowercase() {
+            if !matches!(
+                c,
+                'c' | 'm' | 'i' | 'e' | 'f' | 'b' | 'l' | 'r' | 's' | 'v' | 'h'
+            ) {
+                return Err(BetzaError::UnsupportedModifier(format!(
+                    "Unsupported modifier {} in {}",
+                    c, betza
+                )));
+            }
+            modifiers.push(c);
+            continue;
+        }
+
+        // WW, FF, NN... -> riders
+        let rider = !matches!(c, 'R' | 'B' | 'Q' | 'K') && chars.peek() == Some(&c);
+        if rider {
+            chars.next();
+        }
+
+        let mut atom_moves: Vec<Arc<dyn Move>> = vec![];
+
+        let range = parse_range(&mut chars, betza)?;
+
+        match c {
+            'W' | 'F' | 'R' | 'B' | 'Q' | 'K' => {
+                let range = match (c, rider) {
+                    ('W' | 'F' | 'K', false) => range.or(Some(1)),
+                    _ => range,
+                };
+
+                if matches!(c, 'W' | 'R' | 'Q' | 'K') {
+                    atom_moves.push(Arc::new(Line::new(range)));
+                }
+                if matches!(c, 'F' | 'B' | 'Q' | 'K') {
+                    atom_moves.push(Arc::new(Diagonal::new(range)));
+                }
+            }
+            'N' | 'D' | 'A' | 'H' | 'C' | 'Z' | 'G' => {
+                let (first, second) = match c {
+                    'N' => (2, 1),
+                    'D' => (2, 0),
+                    'A' => (2, 2),
+                    'H' => (3, 0),
+                    'C' => (3, 1),
+                    'Z' => (3, 2),
+                    _ => (3, 3), // G
+                };
+                let jump = Jump { first, second };
+                match rider || range.is_some() {
+                    true => atom_moves.push(Arc::new(Repeat::new(jump, range))),
+                    false => atom_moves.push(Arc::new(jump)),
+                }
+            }
+            _ => {
+                return Err(BetzaError::InvalidAtom(format!(
+                    "Invalid atom {} in {}",
+                    c, betza
+                )))
+            }
+        }
+
+        let directions = directions(&modifiers);
+        if directions.iter().any(|d| d.starts_with('h')) {
+            return Err(BetzaError::UnsupportedModifier(format!(
+                "h needs a direction before it in {}",
+                betza
+            )));
+        }
+
+        // `cm` is the same as no modifier
+        let en_passant = modifiers.contains(&'e');
+        let capture = modifiers.contains(&'c') || en_passant;
+        let no_capture = modifiers.contains(&'m');
+        let initial = modifiers.contains(&'i');
+        for mut m in atom_moves {
+            if !directions.is_empty() {
+                m = Arc::new(Directions::new(m, directions.clone()));
+            }
+            if capture && !no_capture {
+                m = match en_passant {
+                    true => Arc::new(CaptureOnly::with_en_passant(m)),
+                    false => Arc::new(CaptureOnly::new(m)),
+                };
+            }
+            if no_capture && !capture {
+                m = Arc::new(NoCapture::new(m));
+            }
+            if initial {
+                m = Arc::new(FirstMoveOnly::new(m));
+            }
+            moves.push(m);
+        }
+        modifiers.clear();
+    }
+
+    if !modifiers.is_empty() {
+        return Err(BetzaError::InvalidAtom(format!(
+            "Modifiers without an atom in {}",
+            betza
+        )));
+    }
+
+    Ok(moves)
+}
+
+/// Groups the direction modifiers of an atom: `fl`, `fs`, `ff` or `fh` are
+/// compounds, `fb` or `vs` are two directions
+fn directions(modifiers: &[char]) -> Vec<String> {
+    let mut directions: Vec<String> = vec![];
+
+    for &c in modifiers {
+        if !matches!(c, 'f' | 'b' | 'l' | 'r' | 's' | 'v' | 'h') {
+            continue;
+        }
+
+        let compound = match directions.last().map(|d| d.as_bytes()) {
+            Some(&[first]) => match first as char {
+                first if first == c => true,
+                'f' | 'b' => matches!(c, 'l' | 'r' | 's' | 'h'),
+                'l' | 'r' => matches!(c, 'f' | 'b' | 'v' | 'h'),
+                _ => false,
+            },
+            _ => false,
+        };
+
+        match (compound, directions.last_mut()) {
+            (true, Some(last)) => last.push(c),
+            _ => directions.push(c.to_string()),
+        }
+    }
+
+    directions
+}
+
+/// Parses the optional numeric range after an atom
+fn parse_range(chars: &mut Peekable<Chars>, betza: &str) -> Result<Option<u32>, BetzaError> {
+    let mut digits = String::new();
+    while let Some(c) = chars.peek() {
+        if !c.is_ascii_digit() {
+            break;
+        }
+        digits.push(*c);
+        chars.next();
+    }
+
+    if digits.is_empty() {
+        return Ok(None);
+    }
+
+    match digits.parse::<u32>() {
+        Ok(0) | Err(_) => Err(BetzaError::InvalidRange(format!(
+            "Invalid range {} in {}",
+            digits, betza
+        ))),
+        Ok(n) => Ok(Some(n)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Board, Coord};
+    use crate::piece::{Color, Piece, PieceType};
+
+    fn board_with(betza: &str, from: Coord) -> Board {
+        let mut board = Board::new(None, None);
+        let moves = parse(betza).unwrap();
+        board.set_piece(Piece::new(
+            Color::White,
+            PieceType::Custom('x'),
+            moves,
+            from,
+        ));
+        board
+    }
+
+    fn n_moves(board: &Board, from: &Coord) -> usize {
+        board.get_moves(from, false).len()
+    }
+
+    #[test]
+    fn test_standard_pieces() {
+        let from = Coord { row: 3, col: 3 };
+
+        assert_eq!(n_moves(&board_with("N", from), &from), 8);
+        assert_eq!(n_moves(&board_with("WF", from), &from), 8);
+        assert_eq!(n_moves(&board_with("K", from), &from), 8);
+        assert_eq!(n_moves(&board_with("R", from), &from), 14);
+        assert_eq!(n_moves(&board_with("WW", from), &from), 14);
+        assert_eq!(n_moves(&board_with("B", from), &from), 13);
+        assert_eq!(n_moves(&board_with("Q", from), &from), 27);
+        assert_eq!(n_moves(&board_with("BN", from), &from), 21);
+    }
+
+    #[test]
+    fn test_leapers_and_ranges() {
+        let from = Coord { row: 3, col: 3 };
+
+        assert_eq!(n_moves(&board_with("D", from), &from), 4);
+        assert_eq!(n_moves(&board_with("A", from), &from), 4);
+        assert_eq!(n_moves(&board_with("R2", from), &from), 8);
+
+        let board = board_with("C", from);
+        assert!(board.can_move(&from, &Coord { row: 0, col: 4 }, false));
+        assert!(!board.can_move(&from, &Coord { row: 1, col: 4 }, false));
+    }
+
+    #[test]
+    fn test_modifiers() {
+        // from d2: 4 W steps, 3 D leaps from the starting rank, and the F
+        // steps only capture
+        let from = Coord { row: 6, col: 3 };
+        let mut board = board_with("mWimDcF", from);
+        assert_eq!(n_moves(&board, &from), 7);
+        let enemy = Coord { row: 5, col: 4 };
+        board.set_piece(Piece::new(Color::Black, PieceType::Pawn, vec![], enemy));
+        assert_eq!(n_moves(&board, &from), 8);
+        assert!(!board.can_move(&from, &Coord { row: 5, col: 2 }, false));
+
+        let from = Coord { row: 5, col: 3 };
+        assert_eq!(n_moves(&board_with("mWimD", from), &from), 4);
+
+        // knight rider limited to 2 jumps
+        let from = Coord { row: 3, col: 3 };
+        assert_eq!(n_moves(&board_with("NN", from), &from), 12);
+        assert_eq!(n_moves(&board_with("NN1", from), &from), 8);
+        assert!(matches!(parse("Nc"), Err(BetzaError::InvalidAtom(_))));
+    }
+
+    #[test]
+    fn test_directions() {
+        let from = Coord { row: 3, col: 3 };
+
+        assert_eq!(n_moves(&board_with("fN", from), &from), 4);
+        assert_eq!(n_moves(&board_with("ffN", from), &from), 2);
+        assert_eq!(n_moves(&board_with("fsN", from), &from), 2);
+        assert_eq!(n_moves(&board_with("fhNbbN", from), &from), 6);
+        assert_eq!(n_moves(&board_with("flF", from), &from), 1);
+        assert_eq!(n_moves(&board_with("fbR", from), &from), 7);
+        assert_eq!(n_moves(&board_with("sW", from), &from), 2);
+        assert_eq!(n_moves(&board_with("W3", from), &from), 12);
+        assert_eq!(n_moves(&board_with("N2", from), &from), 12);
+    }
+
+    #[test]
+    fn test_pawn() {
+        // e5 pawn: d5 just moved two cells, f6 can be captured, e4 is behind
+        let mut board = Board::from_fen("4k3/8/5n2/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        let e5 = Coord::from_rank_file(4, 4, 8);
+        let pawn = parse("fmWfceF").unwrap();
+        board.set_piece(Piece::new(Color::White, PieceType::Pawn, pawn, e5));
+
+        let mut moves = board.get_moves(&e5, false);
+        moves.sort();
+        assert_eq!(
+            moves,
+            [
+                Coord::from_rank_file(5, 3, 8),
+                Coord::from_rank_file(5, 4, 8),
+                Coord::from_rank_file(5, 5, 8)
+            ]
+        );
+
+        // The black pawn in d5 moves the other way, and its double step needs
+        // the starting rank
+        let d5 = Coord::from_rank_file(4, 3, 8);
+        let pawn = parse("fmWfceFifmW2").unwrap();
+        board.set_piece(Piece::new(Color::Black, PieceType::Pawn, pawn, d5));
+        assert_eq!(
+            board.get_moves(&d5, true),
+            [Coord::from_rank_file(3, 3, 8)]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(parse("X"), Err(BetzaError::InvalidAtom(_))));
+        assert!(matches!(parse("R0"), Err(BetzaError::InvalidRange(_))));
+        assert!(matches!(
+            parse("hN"),
+            Err(BetzaError::UnsupportedModifier(_))
+        ));
+        assert!(matches!(
+            parse("pN"),
+            Err(BetzaError::UnsupportedModifier(_))
+        ));
+    }
+}
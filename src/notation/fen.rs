@@ -1,10 +1,9 @@
 use crate::{
-    board::{BoardInfo, CastlingRights, Coord},
+    board::{Board, BoardInfo, CastlingRights, Coord},
     piece::{Color, Piece},
 };
-use lazy_static::lazy_static;
+#[cfg(feature = "python")]
 use pyo3::{exceptions::PyValueError, PyErr};
-use regex::Regex;
 use std::collections::{HashMap, LinkedList};
 
 use super::AlgebraicNotation;
@@ -17,25 +16,55 @@ pub enum FenError {
     InvalidGameInfo(String),
 }
 
+#[cfg(feature = "python")]
 impl std::convert::From<FenError> for PyErr {
     fn from(err: FenError) -> PyErr {
         PyValueError::new_err(format!("{:?}", err))
     }
 }
 
-lazy_static! {
-    static ref FEN_REGEX: Regex = Regex::new(
-        r"^((([pnbrqkPNBRQK1-8]{1,8})/?){8})\s+(b|w)\s+(-|[KQkq]{0,4})\s+(-|[a-h][3-6])\s+(\d+)\s+(\d+)\s*",
-    )
-    .unwrap();
-}
-
-const OFFICIAL_BOARD_COLS: i32 = 8;
+/// Largest board `parse_sized` accepts, checked before the board is allocated
+pub const MAX_ROWS: u32 = 64;
+pub const MAX_COLS: u32 = 64;
 
 pub const INITIAL_BOARD: &str = r"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
 
+/// Checks the shape of a standard 8x8 FEN: 8 ranks of pieces and digits, the
+/// turn, castling rights, en passant cell and both move counters, with an
+/// optional three-check field (`3+3`) before the counters. Anything after the
+/// counters is ignored.
+///
+/// The number of cells in each rank is checked later, by `parse_sized`.
 pub fn is_valid(fen: &str) -> bool {
-    FEN_REGEX.is_match(fen)
+    let mut fields = fen.split_whitespace();
+    let ranks: Vec<&str> = match fields.next() {
+        Some(placement) => placement.split('/').collect(),
+        None => return false,
+    };
+    let valid_rank = |rank: &&str| {
+        (1..=8).contains(&rank.len()) && rank.chars().all(|c| "pnbrqkPNBRQK12345678".contains(c))
+    };
+    if ranks.len() != 8 || !ranks.iter().all(valid_rank) {
+        return false;
+    }
+
+    let info: Vec<&str> = fields.collect();
+    let is_number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    let is_checks = |s: &str| match s.as_bytes() {
+        [white, b'+', black] => white.is_ascii_digit() && black.is_ascii_digit(),
+        _ => false,
+    };
+    let counters = match info.get(3) {
+        Some(field) if is_checks(field) => 4,
+        _ => 3,
+    };
+
+    info.len() >= counters + 2
+        && matches!(info[0], "w" | "b")
+        && (info[1] == "-" || (info[1].len() <= 4 && info[1].chars().all(|c| "KQkq".contains(c))))
+        && (info[2] == "-" || matches!(info[2].as_bytes(), [b'a'..=b'h', b'3'..=b'6']))
+        && is_number(info[counters])
+        && is_number(info[counters + 1])
 }
 
 fn char_to_piece(c: char, row: i32, col: i32) -> Result<Piece, FenError> {
@@ -71,8 +100,13 @@ fn char_to_piece(c: char, row: i32, col: i32) -> Result<Piece, FenError> {
 /// 3. En passant target cell
 /// 4. Halfmove clock
 /// 5. Fullmove number
-///  
-fn parse_board_info(last_row: Vec<&str>) -> Result<BoardInfo, FenError> {
+///
+/// `rows` and `cols` are the board size, the castling rights refer to its corners.
+///
+/// Three-check FENs have an extra field, see `split_checks`.
+fn parse_board_info(last_row: Vec<&str>, rows: u32, cols: u32) -> Result<BoardInfo, FenError> {
+    let (last_row, checks) = split_checks(last_row)?;
+
     if last_row.len() != 5 {
         return Err(FenError::InvalidGameInfo(format!(
             "Incorrect number of game info, expected 5, got {}",
@@ -90,23 +124,33 @@ fn parse_board_info(last_row: Vec<&str>) -> Result<BoardInfo, FenError> {
             )))
         }
     };
+    let (last, right) = (rows as i32 - 1, cols as i32 - 1);
     let mut castling_rights = HashMap::new();
     for c in last_row[1].chars() {
         let (color, coord, rook) = match c {
             'K' => (
                 Color::White,
-                Coord { row: 7, col: 6 },
-                Coord { row: 7, col: 7 },
+                Coord {
+                    row: last,
+                    col: right - 1,
+                },
+                Coord {
+                    row: last,
+                    col: right,
+                },
             ),
             'Q' => (
                 Color::White,
-                Coord { row: 7, col: 2 },
-                Coord { row: 7, col: 0 },
+                Coord { row: last, col: 2 },
+                Coord { row: last, col: 0 },
             ),
             'k' => (
                 Color::Black,
-                Coord { row: 0, col: 6 },
-                Coord { row: 0, col: 7 },
+                Coord {
+                    row: 0,
+                    col: right - 1,
+                },
+                Coord { row: 0, col: right },
             ),
             'q' => (
                 Color::Black,
@@ -130,7 +174,7 @@ fn parse_board_info(last_row: Vec<&str>) -> Result<BoardInfo, FenError> {
             });
     }
 
-    let alg_parser = AlgebraicNotation { rows: 8, cols: 8 };
+    let alg_parser = AlgebraicNotation { rows, cols };
 
     let en_passant = match last_row[2] {
         "-" => None,
@@ -139,7 +183,7 @@ fn parse_board_info(last_row: Vec<&str>) -> Result<BoardInfo, FenError> {
             Err(_) => {
                 return Err(FenError::InvalidGameInfo(format!(
                     "Invalid en passant {}",
-                    last_row[3]
+                    last_row[2]
                 )))
             }
         },
@@ -150,7 +194,7 @@ fn parse_board_info(last_row: Vec<&str>) -> Result<BoardInfo, FenError> {
         Err(_) => {
             return Err(FenError::InvalidGameInfo(format!(
                 "Invalid halfmove clock {}",
-                last_row[4]
+                last_row[3]
             )))
         }
     };
@@ -160,7 +204,7 @@ fn parse_board_info(last_row: Vec<&str>) -> Result<BoardInfo, FenError> {
         Err(_) => {
             return Err(FenError::InvalidGameInfo(format!(
                 "Invalid fullmove number {}",
-                last_row[5]
+                last_row[4]
             )))
         }
     };
@@ -171,9 +215,56 @@ fn parse_board_info(last_row: Vec<&str>) -> Result<BoardInfo, FenError> {
         en_passant,
         halfmove_clock,
         fullmove_number,
+        check_limit: checks.as_ref().map(|_| THREE_CHECK_LIMIT),
+        checks: checks.unwrap_or_default(),
+        ..BoardInfo::default()
     })
 }
 
+/// Checks needed to win a three-check game
+pub const THREE_CHECK_LIMIT: u32 = 3;
+
+/// Checks given by each color
+type Checks = HashMap<Color, u32>;
+
+/// Takes the three-check field out of the game info, if there is one. Both
+/// common forms are accepted:
+/// - Remaining checks after the en passant cell: `w KQkq - 3+3 0 1`
+/// - Checks given at the end: `w KQkq - 0 1 +0+0`
+///
+/// Returns the other fields and the checks given by each color.
+fn split_checks(mut fields: Vec<&str>) -> Result<(Vec<&str>, Option<Checks>), FenError> {
+    if fields.len() != 6 {
+        return Ok((fields, None));
+    }
+
+    let (field, remaining) = match fields[5].starts_with('+') {
+        true => (fields.remove(5), false),
+        false => (fields.remove(3), true),
+    };
+    let invalid = || FenError::InvalidGameInfo(format!("Invalid three-check field {}", field));
+
+    let (white, black) = field
+        .strip_prefix('+')
+        .unwrap_or(field)
+        .split_once('+')
+        .ok_or_else(invalid)?;
+    let mut checks = HashMap::new();
+    for (color, n) in [(Color::White, white), (Color::Black, black)] {
+        let n: u32 = n.parse().map_err(|_| invalid())?;
+        if n > THREE_CHECK_LIMIT {
+            return Err(invalid());
+        }
+        let given = match remaining {
+            true => THREE_CHECK_LIMIT - n,
+            false => n,
+        };
+        checks.insert(color, given);
+    }
+
+    Ok((fields, Some(checks)))
+}
+
 /// Parse function for *FEN* notation
 ///
 /// The FEN String represents the board state.
@@ -181,60 +272,176 @@ fn parse_board_info(last_row: Vec<&str>) -> Result<BoardInfo, FenError> {
 /// https://en.wikipedia.org/wiki/Forsyth%E2%80%93Edwards_Notation
 pub fn parse(fen: &str) -> Result<(LinkedList<Piece>, BoardInfo), FenError> {
     if !is_valid(fen) {
-        return Err(FenError::InvalidFen(format!(
-            "Invalid FEN (Regex): {}",
-            fen
-        )));
+        return Err(FenError::InvalidFen(format!("Invalid FEN: {}", fen)));
     }
 
-    let mut pieces = LinkedList::new();
-
-    let mut rows: Vec<&str> = fen.split("/").collect();
+    let (pieces, info, _, _) = parse_sized(fen)?;
+    Ok((pieces, info))
+}
 
-    // Get last row
-    let info_row = rows.pop().unwrap();
-    let mut info_row = info_row.split_whitespace(); // remove everything after the whitespace
+/// Same as `parse`, for boards of any size: the number of rows is the number of
+/// ranks in the FEN, and empty cells can be counted with several digits
+/// (e.g. `10` in a 10x10 board). Returns the pieces, the info, rows and columns.
+pub fn parse_sized(fen: &str) -> Result<(LinkedList<Piece>, BoardInfo, u32, u32), FenError> {
+    let mut pieces = LinkedList::new();
 
-    // Removes the firest item from the iterator -> the last row
-    rows.push(info_row.next().unwrap()); // remove everything after the whitespace
+    let mut fields = fen.split_whitespace();
+    let placement = match fields.next() {
+        Some(placement) => placement,
+        None => return Err(FenError::InvalidFen("Empty FEN".to_string())),
+    };
+    let rows: Vec<&str> = placement.split('/').collect();
+    if rows.len() as u32 > MAX_ROWS {
+        return Err(FenError::InvalidFen(format!(
+            "Invalid Fen, {} rows is more than {}",
+            rows.len(),
+            MAX_ROWS
+        )));
+    }
 
-    let board_info = parse_board_info(info_row.collect())?;
+    let too_wide = |row_idx: usize| {
+        FenError::InvalidFen(format!(
+            "Invalid Fen, row {} has more than {} columns",
+            row_idx, MAX_COLS
+        ))
+    };
+    let mut n_cols = None;
 
     // For each row
     for (row_idx, row) in rows.iter().enumerate() {
         let mut col = 0;
+        let mut empty: i32 = 0;
         // For each element in the row
         for c in row.chars() {
-            if c.is_digit(10) {
-                col += c.to_digit(10).unwrap() as i32;
-            } else if c.is_alphabetic() {
-                let piece = char_to_piece(c, row_idx as i32, col)?;
-                pieces.push_back(piece);
-                col += 1;
-            } else {
-                unreachable!("Invalid Fen that has passed the regex check");
+            if let Some(digit) = c.to_digit(10) {
+                empty = match empty
+                    .checked_mul(10)
+                    .and_then(|e| e.checked_add(digit as i32))
+                {
+                    Some(empty) if col + empty <= MAX_COLS as i32 => empty,
+                    _ => return Err(too_wide(row_idx)),
+                };
+                continue;
             }
+
+            col += empty;
+            empty = 0;
+            if col >= MAX_COLS as i32 {
+                return Err(too_wide(row_idx));
+            }
+            let piece = char_to_piece(c, row_idx as i32, col)?;
+            pieces.push_back(piece);
+            col += 1;
         }
+        col += empty;
+
+        match n_cols {
+            None => n_cols = Some(col),
+            Some(n_cols) if n_cols != col => {
+                return Err(FenError::InvalidFen(format!(
+                    "Invalid Fen, row {} has {} columns instead of {}",
+                    row_idx, col, n_cols
+                )))
+            }
+            _ => (),
+        }
+    }
 
-        if col != OFFICIAL_BOARD_COLS {
-            return Err(FenError::InvalidFen(format!(
-                "Invalid Fen, row {} has {} columns instead of {}",
-                row_idx, col, OFFICIAL_BOARD_COLS
-            )));
+    let n_rows = rows.len() as u32;
+    let n_cols = n_cols.unwrap_or(0) as u32;
+    if n_cols == 0 {
+        return Err(FenError::InvalidFen(format!(
+            "Invalid Fen, empty board {}",
+            fen
+        )));
+    }
+
+    let board_info = parse_board_info(fields.collect(), n_rows, n_cols)?;
+
+    Ok((pieces, board_info, n_rows, n_cols))
+}
+
+/// FEN of a board of any size (the inverse of `parse_sized`). Three-check
+/// boards get the remaining checks field before the move counters.
+pub fn write(board: &Board) -> String {
+    let (rows, cols) = (board.get_rows(), board.get_cols());
+    let info = &board.info;
+
+    let ranks: Vec<String> = (0..rows as i32)
+        .map(|row| {
+            let mut rank = String::new();
+            let mut empty = 0;
+            for col in 0..cols as i32 {
+                match board.get_piece(&Coord { row, col }) {
+                    Ok(Some(piece)) => {
+                        if empty > 0 {
+                            rank.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        rank.push(match piece.color {
+                            Color::White => piece.piece.to_char().to_ascii_uppercase(),
+                            Color::Black => piece.piece.to_char(),
+                        });
+                    }
+                    _ => empty += 1,
+                }
+            }
+            if empty > 0 {
+                rank.push_str(&empty.to_string());
+            }
+            rank
+        })
+        .collect();
+
+    let mut castling = String::new();
+    for (color, kingside, queenside) in [(Color::White, 'K', 'Q'), (Color::Black, 'k', 'q')] {
+        let rights = info.castling.get(&color).map(Vec::as_slice).unwrap_or(&[]);
+        if rights.iter().any(|r| r.rook.col > r.new_king.col) {
+            castling.push(kingside);
+        }
+        if rights.iter().any(|r| r.rook.col < r.new_king.col) {
+            castling.push(queenside);
         }
     }
+    if castling.is_empty() {
+        castling.push('-');
+    }
+
+    let notation = AlgebraicNotation { rows, cols };
+    let en_passant = info
+        .en_passant
+        .and_then(|coord| notation.cell_to_str(&coord).ok())
+        .unwrap_or_else(|| "-".to_string());
+
+    let turn = match info.turn {
+        Color::White => "w",
+        Color::Black => "b",
+    };
+
+    let mut fields = vec![ranks.join("/"), turn.to_string(), castling, en_passant];
+    if info.check_limit == Some(THREE_CHECK_LIMIT) {
+        let remaining = |color| info.remaining_checks(color).unwrap_or(0);
+        fields.push(format!(
+            "{}+{}",
+            remaining(Color::White),
+            remaining(Color::Black)
+        ));
+    }
+    fields.push(info.halfmove_clock.to_string());
+    fields.push(info.fullmove_number.to_string());
 
-    Ok((pieces, board_info))
+    fields.join(" ")
 }
 
+#[cfg(test)]
 mod tests {
 
     use crate::{
-        board::{Coord, HasCoordinates},
+        board::{BoardInfo, Coord, HasCoordinates},
         piece::Color,
     };
 
-    use super::{is_valid, parse, INITIAL_BOARD};
+    use super::{is_valid, parse, parse_sized, write, Board, INITIAL_BOARD, MAX_COLS, MAX_ROWS};
 
     #[test]
     fn test_fen_regex() {
@@ -257,6 +464,16 @@ mod tests {
             !is_valid(fen),
             "Fen is invalid, contains a row with 9 pieces"
         );
+
+        let board = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR";
+        assert!(is_valid(&format!("{} b Kq e3 12 40", board)));
+        assert!(is_valid(&format!("{} w KQkq - 3+3 0 1", board)));
+        assert!(is_valid(&format!("{} w KQkq - 0 1 +0+0", board)));
+        assert!(!is_valid(&format!("{} x KQkq - 0 1", board)));
+        assert!(!is_valid(&format!("{} w KQkx - 0 1", board)));
+        assert!(!is_valid(&format!("{} w KQkq e9 0 1", board)));
+        assert!(!is_valid(&format!("{} w KQkq - 0", board)));
+        assert!(!is_valid(&format!("{} w KQkq - a 1", board)));
     }
 
     #[test]
@@ -328,6 +545,110 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sized() {
+        let fen = "rnbqkbnr2/10/10/10/10/10/10/10/10/2RNBQKBNR w K e3 0 1";
+        let (pieces, info, rows, cols) = parse_sized(fen).unwrap();
+
+        assert_eq!((rows, cols), (10, 10));
+        assert_eq!(pieces.len(), 16);
+        assert!(pieces.iter().any(|p| p.coord == Coord { row: 9, col: 9 }));
+        assert_eq!(info.en_passant, Some(Coord { row: 7, col: 4 }));
+        assert_eq!(
+            info.castling.get(&Color::White).unwrap()[0].rook,
+            Coord { row: 9, col: 9 }
+        );
+
+        assert!(parse_sized("8/9 w - - 0 1").is_err());
+        assert!(parse_sized("8/8 w - - 0 b").is_err());
+        assert!(parse_sized("").is_err());
+
+        // Overflowing or huge empty counts fail before allocating the board
+        assert!(parse_sized("99999999999 w - - 0 1").is_err());
+        assert!(Board::from_fen("99999999999 w - - 0 1").is_err());
+        assert!(Board::from_fen("99999999 w - - 0 1").is_err());
+        assert!(parse_sized(&"8/".repeat(MAX_ROWS as usize)).is_err());
+        assert!(parse_sized(&format!("{} w - - 0 1", MAX_COLS)).is_ok());
+    }
+
+    #[test]
+    fn test_fuzz_crashes() {
+        // Inputs that panicked the `fen` fuzz target, run through the same checks
+        let crashes = [
+            "8/8 w - - 0 b",
+            "8/8 w - z9 0 1",
+            "4k3/8/8/8/8/8/1K6/R6R w KQ - 0 1",
+            "99999999999 w - - 0 1",
+            "99999999 w - - 0 1",
+        ];
+
+        for fen in crashes {
+            if let Ok(board) = Board::from_fen(fen) {
+                assert!(board == board.clone());
+                for (from, to) in board.move_gen(None, &[]) {
+                    board.is_legal(&from, &to);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_three_check() {
+        let (_, info) = parse(INITIAL_BOARD).unwrap();
+        assert_eq!(info.check_limit, None);
+
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 3+1 0 1";
+        let (_, info) = parse(fen).unwrap();
+        assert_eq!(info.check_limit, Some(3));
+        assert_eq!(info.checks_given(Color::White), 0);
+        assert_eq!(info.remaining_checks(Color::Black), Some(1));
+        assert_eq!(info.fullmove_number, 1);
+
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 +3+0";
+        let (_, info) = parse(fen).unwrap();
+        assert_eq!(info.checks_given(Color::White), 3);
+        assert_eq!(info.check_limit_winner(), Some(Color::White));
+
+        assert!(parse("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 +4+0").is_err());
+        assert!(parse("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 3").is_err());
+    }
+
+    #[test]
+    fn test_three_check_round_trip() {
+        let mut board = Board::default();
+        board.info = BoardInfo {
+            castling: board.info.castling.clone(),
+            ..BoardInfo::three_check()
+        };
+        board.info.add_check(Color::Black);
+        board.info.add_check(Color::Black);
+
+        let fen = board.to_fen();
+        assert_eq!(
+            fen,
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 3+1 0 1"
+        );
+        let read = Board::from_fen(&fen).unwrap();
+        assert_eq!(read.info.check_limit, Some(3));
+        assert_eq!(read.info.checks_given(Color::White), 0);
+        assert_eq!(read.info.checks_given(Color::Black), 2);
+        assert!(read == board);
+        assert_eq!(read.to_fen(), fen);
+    }
+
+    #[test]
+    fn test_write() {
+        for fen in [
+            INITIAL_BOARD,
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R b Kq - 3 17",
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 2+3 0 1",
+            "rnbqkbnr2/10/10/10/10/10/10/10/10/2RNBQKBNR w K - 0 1",
+        ] {
+            assert_eq!(write(&Board::from_fen(fen).unwrap()), fen);
+        }
+    }
+
     #[test]
     fn test_row_color() {
         // Tests that row 0 is black and row 7 is black
@@ -1,4 +1,11 @@
 mod algebraic;
+pub mod betza;
 pub mod fen;
-pub use algebraic::{AlgebraicNotation, AlgebraicNotationError};
+pub mod pgn;
+pub mod polyglot;
+pub mod puzzle;
+pub use algebraic::{file_from_str, file_to_str, AlgebraicNotation, AlgebraicNotationError};
+pub use betza::BetzaError;
 pub use fen::FenError;
+pub use pgn::PgnGame;
+pub use puzzle::{Puzzle, PuzzleError};
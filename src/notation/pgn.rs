@@ -0,0 +1,177 @@
+/// Tokens that end a game's movetext
+const RESULTS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+/// A game read from a PGN file: its tag pairs and the main line
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PgnGame {
+    /// Tag pairs in file order, e.g. `("White", "Carlsen")`
+    pub headers: Vec<(String, String)>,
+    /// Main line moves in SAN, without move numbers or annotations
+    pub moves: Vec<String>,
+    /// "1-0", "0-1", "1/2-1/2" or "*" (unknown or unfinished)
+    pub result: String,
+}
+
+impl PgnGame {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Parses every game of a PGN file.
+///
+/// Parsing is lenient, as it is meant for large corpora: comments, NAGs and
+/// variations are skipped, malformed tags are ignored, and a game without a
+/// result token is kept with "*". Moves are not validated here.
+pub fn parse_games(pgn: &str) -> Vec<PgnGame> {
+    let mut games = vec![];
+    let mut game = PgnGame::default();
+    let mut movetext = String::new();
+
+    for line in pgn.lines() {
+        let line = line.trim();
+
+        if line.starts_with('[') && !movetext.trim().is_empty() {
+            // Tags after a movetext without result: a new game starts
+            finish_game(&mut games, &mut game, &mut movetext);
+        }
+
+        match line.starts_with('[') {
+            true => game.headers.extend(parse_tag(line)),
+            false => {
+                movetext.push_str(line);
+                movetext.push('\n');
+            }
+        }
+
+        if RESULTS.iter().any(|result| line.ends_with(result)) {
+            finish_game(&mut games, &mut game, &mut movetext);
+        }
+    }
+
+    if !game.headers.is_empty() || !movetext.trim().is_empty() {
+        finish_game(&mut games, &mut game, &mut movetext);
+    }
+
+    games
+}
+
+fn finish_game(games: &mut Vec<PgnGame>, game: &mut PgnGame, movetext: &mut String) {
+    let mut finished = std::mem::take(game);
+    finished.result = "*".to_string();
+
+    for token in movetext_tokens(movetext) {
+        if RESULTS.contains(&token.as_str()) {
+            finished.result = token;
+            break;
+        }
+
+        // "1.", "12...", "1.e4": only what follows the last dot is a move
+        let san = match token.rfind('.') {
+            Some(dot) => &token[dot + 1..],
+            None => token.as_str(),
+        };
+        if san.is_empty() || san.starts_with('$') || san.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        finished.moves.push(san.to_string());
+    }
+
+    movetext.clear();
+    games.push(finished);
+}
+
+/// `[Name "Value"]`
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (name, value) = inner.split_once(' ')?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+
+    Some((name.to_string(), value.replace("\\\"", "\"")))
+}
+
+/// Splits the movetext in whitespace separated tokens, dropping `{comments}`,
+/// `; comments` and `(variations)` (which may be nested)
+fn movetext_tokens(movetext: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut token = String::new();
+    let mut variations = 0;
+    let mut chars = movetext.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => {
+                chars.by_ref().find(|c| *c == '}');
+            }
+            ';' => {
+                chars.by_ref().find(|c| *c == '\n');
+            }
+            '(' => variations += 1,
+            ')' => variations = std::cmp::max(variations - 1, 0),
+            c if !c.is_whitespace() && variations == 0 => {
+                token.push(c);
+                continue;
+            }
+            _ => (),
+        }
+
+        if !token.is_empty() {
+            tokens.push(std::mem::take(&mut token));
+        }
+    }
+
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PGN: &str = r#"[Event "Casual game"]
+[White "Alice"]
+[Black "Bob"]
+[Result "1-0"]
+
+1. e4 e5 2. Nf3 {the main move} Nc6 (2... d6 3. d4 (3. Bc4)) 3. Bb5 $1 a6?!
+4. Ba4 ; Ruy Lopez
+Nf6 5. O-O 1-0
+
+[Event "Unfinished"]
+
+1.d4 d5 2.c4 *
+"#;
+
+    #[test]
+    fn test_parse_games() {
+        let games = parse_games(PGN);
+        assert_eq!(games.len(), 2);
+
+        let game = &games[0];
+        assert_eq!(game.header("White"), Some("Alice"));
+        assert_eq!(game.header("Site"), None);
+        assert_eq!(game.result, "1-0");
+        assert_eq!(
+            game.moves,
+            ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6?!", "Ba4", "Nf6", "O-O"]
+        );
+
+        assert_eq!(games[1].moves, ["d4", "d5", "c4"]);
+        assert_eq!(games[1].result, "*");
+    }
+
+    #[test]
+    fn test_without_result() {
+        let games = parse_games("[White \"A\"]\n\n1. e4 e5\n\n[White \"B\"]\n\n1. d4");
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].moves, ["e4", "e5"]);
+        assert_eq!(games[1].header("White"), Some("B"));
+        assert_eq!(games[1].result, "*");
+    }
+}
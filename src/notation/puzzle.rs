@@ -0,0 +1,127 @@
+use crate::board::Board;
+
+#[derive(Debug, PartialEq)]
+pub enum PuzzleError {
+    InvalidRow(String),
+    InvalidFen(String),
+    InvalidSolution(String),
+}
+
+/// A puzzle of the lichess puzzle database
+/// (`PuzzleId,FEN,Moves,Rating,RatingDeviation,Popularity,NbPlays,Themes,...`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub id: String,
+    /// Position before the opponent's move that sets up the puzzle
+    pub fen: String,
+    /// UCI moves: the opponent's move and then the solution, alternating
+    /// between the solver and the opponent
+    pub moves: Vec<String>,
+    pub rating: u32,
+    pub themes: Vec<String>,
+}
+
+impl Puzzle {
+    pub fn from_csv_row(row: &str) -> Result<Self, PuzzleError> {
+        let invalid = || PuzzleError::InvalidRow(format!("Invalid puzzle row {}", row));
+        let fields: Vec<&str> = row.trim().split(',').collect();
+        if fields.len() < 8 {
+            return Err(invalid());
+        }
+
+        let moves: Vec<String> = fields[2].split_whitespace().map(String::from).collect();
+        if moves.len() < 2 {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            id: fields[0].to_string(),
+            fen: fields[1].to_string(),
+            moves,
+            rating: fields[3].parse().map_err(|_| invalid())?,
+            themes: fields[7].split_whitespace().map(String::from).collect(),
+        })
+    }
+
+    /// Plays the moves, checking that they are legal, and returns the
+    /// position before each move of the solver with the index of that move in
+    /// `Board::sorted_legal_moves`
+    pub fn samples(&self) -> Result<Vec<(Board, usize)>, PuzzleError> {
+        let mut board = Board::from_fen(&self.fen)
+            .map_err(|err| PuzzleError::InvalidFen(format!("{}: {:?}", self.id, err)))?;
+        let mut samples = vec![];
+
+        for (i, uci) in self.moves.iter().enumerate() {
+            let invalid =
+                |err| PuzzleError::InvalidSolution(format!("{}: {} {:?}", self.id, uci, err));
+
+            // the solver plays the odd moves
+            if i % 2 == 1 {
+                let before = board.fork();
+                let record = board.push_uci(uci).map_err(invalid)?;
+                let index = before
+                    .legal_move_index(&(record.from, record.to, record.promotion))
+                    .expect("Pushed moves are legal");
+                samples.push((before, index));
+            } else {
+                board.push_uci(uci).map_err(invalid)?;
+            }
+        }
+
+        Ok(samples)
+    }
+}
+
+/// Reads the puzzles of a CSV file, skipping its header and any malformed row.
+/// Solutions are not validated here, see `Puzzle::samples`.
+pub fn parse_puzzles(csv: &str) -> Vec<Puzzle> {
+    csv.lines()
+        .filter_map(|row| Puzzle::from_csv_row(row).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "PuzzleId,FEN,Moves,Rating,RatingDeviation,Popularity,NbPlays,Themes,GameUrl,OpeningTags
+00008,r6k/pp2r2p/4Rp1Q/3p4/8/1N1P2R1/PqP2bPP/7K b - - 0 24,f2g3 e6e7 b2b1 b3c1 b1c1 h6c1,1913,75,94,6230,crushing hangingPiece long middlegame,https://lichess.org/787zsVup/black#47,
+0000D,5rk1/1p3ppp/pq3b2/8/8/1P1Q1N2/P4PPP/3R2K1 w - - 2 27,d3d6 f8d8 d6d8 f6d8,1580,73,97,11995,advantage endgame short,https://lichess.org/F8M8OS71#53,
+broken row
+";
+
+    #[test]
+    fn test_parse_puzzles() {
+        let puzzles = parse_puzzles(CSV);
+        assert_eq!(puzzles.len(), 2);
+
+        let puzzle = &puzzles[0];
+        assert_eq!(puzzle.id, "00008");
+        assert_eq!(puzzle.rating, 1913);
+        assert_eq!(puzzle.moves.len(), 6);
+        assert_eq!(
+            puzzle.themes,
+            ["crushing", "hangingPiece", "long", "middlegame"]
+        );
+    }
+
+    #[test]
+    fn test_samples() {
+        let puzzles = parse_puzzles(CSV);
+
+        let samples = puzzles[0].samples().unwrap();
+        assert_eq!(samples.len(), 3);
+        for ((board, index), uci) in samples.iter().zip(["e6e7", "b3c1", "h6c1"]) {
+            let (from, to, promotion) = board.sorted_legal_moves()[*index];
+            assert_eq!(board.move_to_uci(&from, &to, promotion).unwrap(), uci);
+        }
+        assert_eq!(puzzles[1].samples().unwrap().len(), 2);
+
+        let mut puzzle = puzzles[0].clone();
+        puzzle.moves[3] = "b3b4".to_string();
+        assert!(matches!(
+            puzzle.samples(),
+            Err(PuzzleError::InvalidSolution(_))
+        ));
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use crate::board::Board;
+use crate::notation::pgn::{parse_games, PgnGame};
+use crate::notation::polyglot::{polyglot_key, polyglot_move, write_entry};
+
+////////////////////////////////////////////////
+// MOVE STATS
+////////////////////////////////////////////////
+
+/// How often a move was played in a position, and how those games ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MoveStats {
+    pub games: u32,
+    pub white_wins: u32,
+    pub draws: u32,
+    pub black_wins: u32,
+}
+
+impl MoveStats {
+    fn add(&mut self, result: &str) {
+        self.games += 1;
+        match result {
+            "1-0" => self.white_wins += 1,
+            "0-1" => self.black_wins += 1,
+            "1/2-1/2" => self.draws += 1,
+            _ => (),
+        }
+    }
+
+    /// White's expected score over the games with a known result
+    pub fn white_score(&self) -> Option<f64> {
+        let decided = self.white_wins + self.draws + self.black_wins;
+        match decided {
+            0 => None,
+            n => Some((self.white_wins as f64 + self.draws as f64 / 2.0) / n as f64),
+        }
+    }
+}
+
+////////////////////////////////////////////////
+// OPENING TREE
+////////////////////////////////////////////////
+
+/// Moves played in the first `max_depth` plies of a set of games, grouped by
+/// position (`Board::position_hash`), so transpositions share their stats.
+#[derive(Debug, Clone)]
+pub struct OpeningTree {
+    pub max_depth: usize,
+    /// Position hash -> UCI move -> stats
+    positions: HashMap<u64, HashMap<String, MoveStats>>,
+    /// Position hash and UCI move -> Polyglot key and move, for standard boards
+    polyglot: HashMap<(u64, String), (u64, u16)>,
+}
+
+impl OpeningTree {
+    pub fn new(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            positions: HashMap::new(),
+            polyglot: HashMap::new(),
+        }
+    }
+
+    /// Adds the opening of a game, returning the number of plies added.
+    ///
+    /// Games with a `FEN` tag start from that position. An invalid FEN or
+    /// move ends the game there, keeping the plies before it.
+    pub fn add_game(&mut self, game: &PgnGame) -> usize {
+        let mut board = match game.header("FEN") {
+            Some(fen) => match Board::from_fen(fen) {
+                Ok(board) => board,
+                Err(_) => return 0,
+            },
+            None => Board::default(),
+        };
+
+        let mut plies = 0;
+        for san in game.moves.iter().take(self.max_depth) {
+            let hash = board.position_hash();
+            let key = polyglot_key(&board);
+            let record = match board.push_san(san) {
+                Ok(record) => record,
+                Err(_) => break,
+            };
+
+            if let Some(key) = key {
+                self.polyglot
+                    .insert((hash, record.uci.clone()), (key, polyglot_move(&record)));
+            }
+
+            self.positions
+                .entry(hash)
+                .or_default()
+                .entry(record.uci)
+                .or_default()
+                .add(&game.result);
+            plies += 1;
+        }
+
+        plies
+    }
+
+    /// Adds every game of a PGN file, returning how many had a valid opening
+    pub fn add_pgn(&mut self, pgn: &str) -> usize {
+        parse_games(pgn)
+            .iter()
+            .filter(|game| self.add_game(game) > 0)
+            .count()
+    }
+
+    /// Moves played from the position with this hash, most played first
+    pub fn moves(&self, position_hash: u64) -> Vec<(String, MoveStats)> {
+        let mut moves: Vec<(String, MoveStats)> = match self.positions.get(&position_hash) {
+            Some(moves) => moves
+                .iter()
+                .map(|(uci, stats)| (uci.clone(), *stats))
+                .collect(),
+            None => vec![],
+        };
+        moves.sort_by(|(a_uci, a), (b_uci, b)| b.games.cmp(&a.games).then(a_uci.cmp(b_uci)));
+        moves
+    }
+
+    /// Same as `moves`, for the position of `board`
+    pub fn moves_for(&self, board: &Board) -> Vec<(String, MoveStats)> {
+        self.moves(board.position_hash())
+    }
+
+    /// The tree as a Polyglot book, weighting each move by the number of games
+    /// it was played in. Positions of non standard boards are left out.
+    pub fn write_polyglot(&self) -> Vec<u8> {
+        // transpositions with different en passant squares share a Polyglot key
+        let mut games: HashMap<(u64, u16), u32> = HashMap::new();
+        for (hash, moves) in &self.positions {
+            for (uci, stats) in moves {
+                if let Some(entry) = self.polyglot.get(&(*hash, uci.clone())) {
+                    *games.entry(*entry).or_default() += stats.games;
+                }
+            }
+        }
+
+        let mut entries: Vec<(u64, u16, u16)> = games
+            .into_iter()
+            .map(|((key, mv), games)| (key, mv, games.min(u16::MAX as u32) as u16))
+            .collect();
+
+        // Sorted by key, as books are binary searched, most played first
+        entries.sort_by(|a, b| a.0.cmp(&b.0).then(b.2.cmp(&a.2)).then(a.1.cmp(&b.1)));
+
+        let mut bytes = vec![];
+        for (key, mv, weight) in entries {
+            write_entry(&mut bytes, key, mv, weight);
+        }
+        bytes
+    }
+
+    /// Number of positions in the tree
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::polyglot::ENTRY_SIZE;
+
+    const PGN: &str = r#"[Result "1-0"]
+
+1. e4 e5 2. Nf3 Nc6 1-0
+
+[Result "1/2-1/2"]
+
+1. Nf3 e5 2. e4 Nc6 3. Bb5 1/2-1/2
+
+[Result "0-1"]
+
+1. e4 c5 2. Qxf7 0-1
+"#;
+
+    #[test]
+    fn test_opening_tree() {
+        let mut tree = OpeningTree::new(5);
+        assert_eq!(tree.add_pgn(PGN), 3);
+
+        let moves = tree.moves_for(&Board::default());
+        assert_eq!(moves.len(), 2);
+        assert_eq!(moves[0].0, "e2e4");
+        assert_eq!(
+            moves[0].1,
+            MoveStats {
+                games: 2,
+                white_wins: 1,
+                draws: 0,
+                black_wins: 1
+            }
+        );
+        assert_eq!(moves[1].1.white_score(), Some(0.5));
+
+        // the second game transposes into the first one, and plays 3. Bb5
+        let mut board = Board::default();
+        for uci in ["e2e4", "e7e5", "g1f3", "b8c6"] {
+            board.push_uci(uci).unwrap();
+        }
+        let moves = tree.moves_for(&board);
+        assert_eq!(moves.len(), 1);
+        assert_eq!((moves[0].0.as_str(), moves[0].1.draws), ("f1b5", 1));
+
+        board.pop();
+        assert_eq!(tree.moves_for(&board)[0].1.games, 1);
+
+        // the illegal Qxf7 is not added
+        assert_eq!(tree.len(), 8);
+    }
+
+    #[test]
+    fn test_write_polyglot() {
+        let mut tree = OpeningTree::new(5);
+        tree.add_pgn(PGN);
+
+        // 1. e4 e5 2. Nf3 and 1. Nf3 e5 2. e4 transpose into one entry
+        let book = tree.write_polyglot();
+        assert_eq!(book.len(), 9 * ENTRY_SIZE);
+
+        let entries: Vec<(u64, u16, u16)> = book
+            .chunks(ENTRY_SIZE)
+            .map(|entry| {
+                (
+                    u64::from_be_bytes(entry[..8].try_into().unwrap()),
+                    u16::from_be_bytes(entry[8..10].try_into().unwrap()),
+                    u16::from_be_bytes(entry[10..12].try_into().unwrap()),
+                )
+            })
+            .collect();
+        assert!(entries.windows(2).all(|w| w[0].0 <= w[1].0));
+
+        // 1. e4 (twice) and 1. Nf3 from the start position
+        let start: Vec<(u16, u16)> = entries
+            .iter()
+            .filter(|(key, _, _)| *key == 0x463b96181691fc9c)
+            .map(|(_, mv, weight)| (*mv, *weight))
+            .collect();
+        assert_eq!(start, [(0x031c, 2), (6 << 6 | 21, 1)]);
+    }
+}
@@ -1,6 +1,7 @@
-use std::collections::HashSet;
-use std::{fmt, rc::Rc};
+use std::collections::BTreeSet;
+use std::{fmt, sync::Arc};
 
+use crate::moves::avoid_capture::AvoidCapture;
 use crate::moves::castle::Castle;
 use crate::moves::diag::Diagonal;
 use crate::moves::jump::Jump;
@@ -8,9 +9,10 @@ use crate::moves::line::Line;
 use crate::moves::PawnMove;
 use crate::Board;
 use crate::{board::Coord, moves::Move};
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 #[derive(Debug, PartialEq, Clone, Copy, Eq, Hash)]
-#[pyclass]
+#[cfg_attr(feature = "python", pyclass)]
 pub enum Color {
     White,
     Black,
@@ -34,7 +36,7 @@ impl fmt::Display for Color {
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Hash)]
 
 pub enum PieceType {
     King,
@@ -43,6 +45,51 @@ pub enum PieceType {
     Bishop,
     Knight,
     Pawn,
+    /// Piece registered in a `PieceRegistry`, identified by its (lowercase) FEN letter
+    Custom(char),
+}
+
+impl PieceType {
+    /// Lowercase FEN letter
+    pub fn to_char(&self) -> char {
+        match self {
+            Self::King => 'k',
+            Self::Queen => 'q',
+            Self::Rook => 'r',
+            Self::Bishop => 'b',
+            Self::Knight => 'n',
+            Self::Pawn => 'p',
+            Self::Custom(c) => *c,
+        }
+    }
+
+    /// Piece code used by `Board::to_array`. Codes are stable: 0 is an empty
+    /// cell, 1 to 6 are pawn, knight, bishop, rook, queen and king, and every
+    /// custom piece is 7.
+    pub fn code(&self) -> u8 {
+        match self {
+            Self::Pawn => 1,
+            Self::Knight => 2,
+            Self::Bishop => 3,
+            Self::Rook => 4,
+            Self::Queen => 5,
+            Self::King => 6,
+            Self::Custom(_) => 7,
+        }
+    }
+
+    /// From a FEN letter in any case, other letters are custom pieces
+    pub fn from_char(c: char) -> Self {
+        match c.to_ascii_lowercase() {
+            'k' => Self::King,
+            'q' => Self::Queen,
+            'r' => Self::Rook,
+            'b' => Self::Bishop,
+            'n' => Self::Knight,
+            'p' => Self::Pawn,
+            c => Self::Custom(c),
+        }
+    }
 }
 
 impl fmt::Display for PieceType {
@@ -54,39 +101,47 @@ impl fmt::Display for PieceType {
             Self::Rook => write!(f, "♜"),
             Self::Knight => write!(f, "♞"),
             Self::Pawn => write!(f, "♟︎"),
+            Self::Custom(c) => write!(f, "{}", c),
         }
     }
 }
 
 #[allow(dead_code)]
 #[derive(Clone)]
-#[pyclass]
+#[cfg_attr(feature = "python", pyclass)]
 
 pub struct Piece {
     pub color: Color,
     pub piece: PieceType,
     // Mutable Cell reference:
     pub coord: Coord,
-    pub moves: Vec<Rc<dyn Move>>,
+    pub moves: Vec<Arc<dyn Move>>,
+    /// Royal pieces can't be left in check (the king by default)
+    pub royal: bool,
 }
 
-unsafe impl Send for Piece {}
-
 impl Piece {
-    pub fn new(color: Color, piece: PieceType, moves: Vec<Rc<dyn Move>>, coord: Coord) -> Self {
+    pub fn new(color: Color, piece: PieceType, moves: Vec<Arc<dyn Move>>, coord: Coord) -> Self {
         Self {
             color,
             piece,
             moves: moves,
             coord,
+            royal: piece == PieceType::King,
         }
     }
 
+    /// Same piece, marked as royal (or not)
+    pub fn with_royal(mut self, royal: bool) -> Self {
+        self.royal = royal;
+        self
+    }
+
     pub fn new_rook(color: Color, coord: Coord) -> Self {
         Self::new(
             color,
             PieceType::Rook,
-            vec![Rc::new(Line::new(None))],
+            vec![Arc::new(Line::new(None))],
             coord,
         )
     }
@@ -95,7 +150,7 @@ impl Piece {
         Self::new(
             color,
             PieceType::Bishop,
-            vec![Rc::new(Diagonal::new(None))],
+            vec![Arc::new(Diagonal::new(None))],
             coord,
         )
     }
@@ -104,7 +159,7 @@ impl Piece {
         Self::new(
             color,
             PieceType::Queen,
-            vec![Rc::new(Line::new(None)), Rc::new(Diagonal::new(None))],
+            vec![Arc::new(Line::new(None)), Arc::new(Diagonal::new(None))],
             coord,
         )
     }
@@ -114,9 +169,9 @@ impl Piece {
             color,
             PieceType::King,
             vec![
-                Rc::new(Line::new(Some(1))),
-                Rc::new(Diagonal::new(Some(1))),
-                Rc::new(Castle::new(Some(2))),
+                Arc::new(AvoidCapture::new(Line::new(Some(1)))),
+                Arc::new(AvoidCapture::new(Diagonal::new(Some(1)))),
+                Arc::new(Castle::new(Some(2))),
             ],
             coord,
         )
@@ -126,13 +181,45 @@ impl Piece {
         Self::new(
             color,
             PieceType::Pawn,
-            vec![Rc::new(PawnMove::new())],
+            vec![Arc::new(PawnMove::new())],
             coord,
         )
     }
 
     pub fn new_knight(color: Color, coord: Coord) -> Self {
-        Self::new(color, PieceType::Knight, vec![Rc::new(Jump::new())], coord)
+        Self::new(color, PieceType::Knight, vec![Arc::new(Jump::new())], coord)
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Piece {
+    #[getter(color)]
+    fn py_color(&self) -> Color {
+        self.color
+    }
+
+    /// FEN letter, uppercase for white pieces
+    #[getter(symbol)]
+    fn py_symbol(&self) -> char {
+        match self.color {
+            Color::White => self.piece.to_char().to_ascii_uppercase(),
+            Color::Black => self.piece.to_char(),
+        }
+    }
+
+    #[getter(coord)]
+    fn py_coord(&self) -> Coord {
+        self.coord
+    }
+
+    #[getter(royal)]
+    fn py_royal(&self) -> bool {
+        self.royal
+    }
+
+    fn __str__(&self) -> String {
+        self.to_string()
     }
 }
 
@@ -143,7 +230,8 @@ impl Piece {
             .any(|m| m.is_move_valid(self.coord, coord, board))
     }
 
-    pub fn get_moves(&self, board: &Board) -> HashSet<Coord> {
+    /// Cells the piece can move to, in `Coord` order
+    pub fn get_moves(&self, board: &Board) -> BTreeSet<Coord> {
         self.moves
             .iter()
             .flat_map(|m| m.allowed_moves(self.coord, board))
@@ -161,6 +249,7 @@ impl fmt::Display for Piece {
                 PieceType::Bishop => "♗",
                 PieceType::Queen => "♕",
                 PieceType::King => "♔",
+                PieceType::Custom(c) => return write!(f, "{}", c.to_ascii_uppercase()),
             },
 
             Color::Black => match self.piece {
@@ -170,6 +259,7 @@ impl fmt::Display for Piece {
                 PieceType::Bishop => "♝",
                 PieceType::Queen => "♛",
                 PieceType::King => "♚",
+                PieceType::Custom(c) => return write!(f, "{}", c.to_ascii_lowercase()),
             },
         };
 
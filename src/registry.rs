@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::board::Coord;
+use crate::moves::castle::Castle;
+use crate::moves::jump::Jump;
+use crate::moves::{AvoidCapture, Diagonal, Line, Move, PawnMove};
+use crate::piece::{Color, Piece, PieceType};
+
+/// Definition of a piece type: how it is named, written in FEN and how it moves
+#[derive(Clone)]
+pub struct PieceDef {
+    pub name: String,
+    /// Lowercase FEN letter. Uppercase is used for white pieces.
+    pub fen: char,
+    pub piece: PieceType,
+    /// Shared by every piece created from this definition
+    pub moves: Vec<Arc<dyn Move>>,
+    /// Royal pieces can't be left in check
+    pub royal: bool,
+}
+
+impl PieceDef {
+    pub fn new(name: &str, fen: char, piece: PieceType, moves: Vec<Arc<dyn Move>>) -> Self {
+        Self {
+            name: name.to_string(),
+            fen,
+            piece,
+            moves,
+            royal: piece == PieceType::King,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RegistryError {
+    InvalidFenChar(String),
+    DuplicatePiece(String),
+    UnknownPiece(String),
+}
+
+/// Set of piece types available in a game, indexed by FEN letter.
+///
+/// Fairy pieces are registered with a composed list of moves, e.g. an
+/// archbishop is a `Diagonal` + a `Jump`:
+///
+/// ```ignore
+/// let mut registry = PieceRegistry::standard();
+/// registry.register("archbishop", 'a', vec![
+///     Arc::new(Diagonal::new(None)),
+///     Arc::new(Jump::new()),
+/// ])?;
+/// let archbishop = registry.create('A', Coord { row: 0, col: 2 });
+/// ```
+#[derive(Clone, Default)]
+pub struct PieceRegistry {
+    pieces: HashMap<char, PieceDef>,
+}
+
+impl PieceRegistry {
+    /// Empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry with the six FIDE pieces
+    pub fn standard() -> Self {
+        let mut registry = Self::new();
+
+        registry.insert(PieceDef::new(
+            "pawn",
+            'p',
+            PieceType::Pawn,
+            vec![Arc::new(PawnMove::new())],
+        ));
+        registry.insert(PieceDef::new(
+            "knight",
+            'n',
+            PieceType::Knight,
+            vec![Arc::new(Jump::new())],
+        ));
+        registry.insert(PieceDef::new(
+            "bishop",
+            'b',
+            PieceType::Bishop,
+            vec![Arc::new(Diagonal::new(None))],
+        ));
+        registry.insert(PieceDef::new(
+            "rook",
+            'r',
+            PieceType::Rook,
+            vec![Arc::new(Line::new(None))],
+        ));
+        registry.insert(PieceDef::new(
+            "queen",
+            'q',
+            PieceType::Queen,
+            vec![Arc::new(Line::new(None)), Arc::new(Diagonal::new(None))],
+        ));
+        registry.insert(PieceDef::new(
+            "king",
+            'k',
+            PieceType::King,
+            vec![
+                Arc::new(AvoidCapture::new(Line::new(Some(1)))),
+                Arc::new(AvoidCapture::new(Diagonal::new(Some(1)))),
+                Arc::new(Castle::new(Some(2))),
+            ],
+        ));
+
+        registry
+    }
+
+    fn insert(&mut self, def: PieceDef) {
+        self.pieces.insert(def.fen, def);
+    }
+
+    /// Registers a new piece type, identified by its FEN letter (case insensitive)
+    pub fn register(
+        &mut self,
+        name: &str,
+        fen: char,
+        moves: Vec<Arc<dyn Move>>,
+    ) -> Result<(), RegistryError> {
+        if !fen.is_ascii_alphabetic() {
+            return Err(RegistryError::InvalidFenChar(format!(
+                "Invalid FEN letter {}",
+                fen
+            )));
+        }
+
+        let fen = fen.to_ascii_lowercase();
+
+        if let Some(def) = self.pieces.get(&fen) {
+            return Err(RegistryError::DuplicatePiece(format!(
+                "{} is already used by {}",
+                fen, def.name
+            )));
+        }
+
+        self.insert(PieceDef::new(name, fen, PieceType::Custom(fen), moves));
+
+        Ok(())
+    }
+
+    pub fn get(&self, fen: char) -> Option<&PieceDef> {
+        self.pieces.get(&fen.to_ascii_lowercase())
+    }
+
+    /// Marks a piece type as royal (or not), e.g. a royal custom piece instead
+    /// of the king, or no royals at all for antichess
+    pub fn set_royal(&mut self, fen: char, royal: bool) -> Result<(), RegistryError> {
+        match self.pieces.get_mut(&fen.to_ascii_lowercase()) {
+            Some(def) => {
+                def.royal = royal;
+                Ok(())
+            }
+            None => Err(RegistryError::UnknownPiece(format!(
+                "Unknown piece {}",
+                fen
+            ))),
+        }
+    }
+
+    /// Creates the piece written as `fen` (uppercase -> white, lowercase -> black)
+    pub fn create(&self, fen: char, coord: Coord) -> Option<Piece> {
+        let def = self.get(fen)?;
+
+        let color = match fen.is_uppercase() {
+            true => Color::White,
+            false => Color::Black,
+        };
+
+        Some(Piece::new(color, def.piece, def.moves.clone(), coord).with_royal(def.royal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Board;
+
+    fn fairy_registry() -> PieceRegistry {
+        let mut registry = PieceRegistry::standard();
+        registry
+            .register(
+                "archbishop",
+                'a',
+                vec![Arc::new(Diagonal::new(None)), Arc::new(Jump::new())],
+            )
+            .unwrap();
+        registry
+            .register(
+                "chancellor",
+                'c',
+                vec![Arc::new(Line::new(None)), Arc::new(Jump::new())],
+            )
+            .unwrap();
+        registry
+    }
+
+    #[test]
+    fn test_standard() {
+        let registry = PieceRegistry::standard();
+
+        let knight = registry.create('N', Coord { row: 7, col: 1 }).unwrap();
+        assert_eq!(knight.piece, PieceType::Knight);
+        assert_eq!(knight.color, Color::White);
+
+        let queen = registry.create('q', Coord { row: 0, col: 3 }).unwrap();
+        assert_eq!(queen.piece, PieceType::Queen);
+        assert_eq!(queen.color, Color::Black);
+
+        assert!(registry.create('x', Coord { row: 0, col: 0 }).is_none());
+    }
+
+    #[test]
+    fn test_standard_king() {
+        // The d8 rook attacks d1 and d2: neither king can step there
+        let mut board = Board::from_fen("3rk3/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        let e1 = Coord::from_rank_file(0, 4, 8);
+        let moves = board.get_moves(&e1, false);
+        assert!(!moves.contains(&Coord::from_rank_file(0, 3, 8)));
+
+        let registry = PieceRegistry::standard();
+        board.set_piece(registry.create('K', e1).unwrap());
+        assert_eq!(board.get_moves(&e1, false), moves);
+    }
+
+    #[test]
+    fn test_register_errors() {
+        let mut registry = fairy_registry();
+
+        assert!(matches!(
+            registry.register("knight2", 'N', vec![]),
+            Err(RegistryError::DuplicatePiece(_))
+        ));
+        assert!(matches!(
+            registry.register("digit", '1', vec![]),
+            Err(RegistryError::InvalidFenChar(_))
+        ));
+    }
+
+    #[test]
+    fn test_royals() {
+        let mut registry = fairy_registry();
+        registry.set_royal('k', false).unwrap();
+        registry.set_royal('C', true).unwrap();
+        assert!(registry.set_royal('x', true).is_err());
+
+        // The black chancellor is the royal piece, attacked by the a1 rook
+        let mut board = Board::new(None, None);
+        for (fen, row, col) in [('K', 7, 4), ('R', 7, 0), ('c', 0, 0), ('k', 0, 4)] {
+            board.set_piece(registry.create(fen, Coord { row, col }).unwrap());
+        }
+
+        assert!(board.is_in_check(Color::Black));
+        assert!(!board.is_in_check(Color::White));
+        assert_eq!(board.royals(&Color::Black), vec![Coord { row: 0, col: 0 }]);
+
+        // no royals: nothing is ever in check
+        registry.set_royal('c', false).unwrap();
+        board.set_piece(registry.create('c', Coord { row: 0, col: 0 }).unwrap());
+        assert!(!board.is_in_check(Color::Black));
+    }
+
+    #[test]
+    fn test_fairy_moves_on_custom_board() {
+        let registry = fairy_registry();
+        let mut board = Board::new(Some(10), Some(10));
+
+        let from = Coord { row: 5, col: 5 };
+        let archbishop = registry.create('A', from).unwrap();
+        assert_eq!(archbishop.piece, PieceType::Custom('a'));
+        assert_eq!(archbishop.to_string(), "A");
+        board.set_piece(archbishop);
+
+        // Bishop + knight
+        assert!(board.can_move(&from, &Coord { row: 1, col: 1 }, false));
+        assert!(board.can_move(&from, &Coord { row: 3, col: 4 }, false));
+        assert!(!board.can_move(&from, &Coord { row: 5, col: 9 }, false));
+
+        let from = Coord { row: 0, col: 0 };
+        let chancellor = registry.create('C', from).unwrap();
+        board.set_piece(chancellor);
+
+        // Rook + knight
+        assert!(board.can_move(&from, &Coord { row: 0, col: 9 }, false));
+        assert!(board.can_move(&from, &Coord { row: 2, col: 1 }, false));
+        assert!(!board.can_move(&from, &Coord { row: 2, col: 2 }, false));
+    }
+}
@@ -0,0 +1,88 @@
+//! Browser bindings (wasm-bindgen).
+//!
+//! Squares are exchanged in algebraic notation (`"e2"`) so the JS side does not
+//! need to know about the internal row/col convention.
+use wasm_bindgen::prelude::*;
+
+use crate::notation::{fen, AlgebraicNotation};
+use crate::{Board, Coord};
+
+#[wasm_bindgen(js_name = Board)]
+pub struct WasmBoard {
+    board: Board,
+}
+
+#[wasm_bindgen(js_class = Board)]
+impl WasmBoard {
+    /// Creates a board from a FEN string, or the initial position if no FEN is given
+    #[wasm_bindgen(constructor)]
+    pub fn new(fen: Option<String>) -> Result<WasmBoard, JsValue> {
+        let board = match fen {
+            Some(fen) => {
+                Board::from_fen(&fen).map_err(|e| JsValue::from_str(&format!("{:?}", e)))?
+            }
+            None => Board::default(),
+        };
+
+        Ok(WasmBoard { board })
+    }
+
+    /// Cells the piece in `square` can move to without leaving its king in check
+    pub fn moves(&self, square: &str) -> Result<Vec<String>, JsValue> {
+        let from = self.parse_cell(square)?;
+
+        // Only the side to move has moves, as in `canMove`
+        let turn = self.board.info.turn;
+        let cells = match self.board.get_piece(&from) {
+            Ok(Some(piece)) if piece.color == turn => self.board.legal_moves(&from),
+            _ => vec![],
+        };
+
+        let notation = self.notation();
+        let mut moves: Vec<String> = cells
+            .iter()
+            .filter_map(|to| notation.cell_to_str(to).ok())
+            .collect();
+        moves.sort();
+
+        Ok(moves)
+    }
+
+    #[wasm_bindgen(js_name = canMove)]
+    pub fn can_move(&self, from: &str, to: &str) -> Result<bool, JsValue> {
+        let from = self.parse_cell(from)?;
+        let to = self.parse_cell(to)?;
+
+        Ok(self.board.can_move(&from, &to, false))
+    }
+
+    #[wasm_bindgen(js_name = toFen)]
+    pub fn to_fen(&self) -> String {
+        self.board.to_fen()
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_string_js(&self) -> String {
+        self.board.to_string()
+    }
+}
+
+impl WasmBoard {
+    fn notation(&self) -> AlgebraicNotation {
+        AlgebraicNotation {
+            rows: self.board.get_rows(),
+            cols: self.board.get_cols(),
+        }
+    }
+
+    fn parse_cell(&self, cell: &str) -> Result<Coord, JsValue> {
+        self.notation()
+            .cell_from_str(cell)
+            .map_err(|e| JsValue::from_str(&format!("{:?}", e)))
+    }
+}
+
+#[wasm_bindgen(js_name = isValidFen)]
+pub fn is_valid_fen(fen: &str) -> bool {
+    fen::is_valid(fen)
+}
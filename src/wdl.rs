@@ -0,0 +1,158 @@
+////////////////////////////////////////////////
+// EXPECTED SCORE
+////////////////////////////////////////////////
+
+/// Slope of the logistic curve mapping centipawns to expected score
+/// (fitted on rated online games, as used by lichess)
+const EXPECTED_SCORE_SLOPE: f64 = 0.003_682_08;
+
+/// Scores past this are treated as won (or lost) positions
+const MAX_CP: i32 = 4000;
+
+/// Expected score (0 = loss, 0.5 = draw, 1 = win) of a centipawn evaluation
+pub fn cp_to_expected_score(cp: i32) -> f64 {
+    let cp = cp.clamp(-MAX_CP, MAX_CP) as f64;
+    1.0 / (1.0 + (-EXPECTED_SCORE_SLOPE * cp).exp())
+}
+
+/// Inverse of `cp_to_expected_score`
+pub fn expected_score_to_cp(score: f64) -> i32 {
+    let score = score.clamp(1e-9, 1.0 - 1e-9);
+    let cp = -(1.0 / score - 1.0).ln() / EXPECTED_SCORE_SLOPE;
+    (cp.round() as i32).clamp(-MAX_CP, MAX_CP)
+}
+
+/// Centipawns to pawns of material, e.g. 150 -> 1.5
+pub fn cp_to_pawns(cp: i32) -> f64 {
+    cp as f64 / 100.0
+}
+
+////////////////////////////////////////////////
+// WIN DRAW LOSS
+////////////////////////////////////////////////
+
+/// Win, draw and loss probabilities from the point of view of the side to move
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Wdl {
+    pub win: f64,
+    pub draw: f64,
+    pub loss: f64,
+}
+
+impl Wdl {
+    pub fn new(win: f64, draw: f64, loss: f64) -> Self {
+        Self { win, draw, loss }
+    }
+
+    /// Win + half of the draws
+    pub fn expected_score(&self) -> f64 {
+        self.win + self.draw / 2.0
+    }
+
+    /// Value in [-1, 1], the usual value head target
+    pub fn value(&self) -> f64 {
+        self.win - self.loss
+    }
+
+    /// Same probabilities from the opponent's point of view
+    pub fn flip(&self) -> Self {
+        Self::new(self.loss, self.draw, self.win)
+    }
+}
+
+/// Win rate model: `win(cp) = 1 / (1 + exp((a - cp) / b))`, where `a` and `b`
+/// are cubic polynomials of the game phase (ply / 64, capped at ply 240).
+///
+/// `a` is the evaluation at which the game is won half of the time, and `b`
+/// how spread out the results are around it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WdlModel {
+    pub a_coefs: [f64; 4],
+    pub b_coefs: [f64; 4],
+    /// Units of the fitted coefficients in 100 centipawns
+    pub pawn_value: f64,
+}
+
+impl WdlModel {
+    pub fn new(a_coefs: [f64; 4], b_coefs: [f64; 4], pawn_value: f64) -> Self {
+        Self {
+            a_coefs,
+            b_coefs,
+            pawn_value,
+        }
+    }
+
+    /// Model fitted by Stockfish on its own self-play games, normalized so
+    /// that +100cp wins half of the games at move 32
+    pub fn stockfish() -> Self {
+        Self::new(
+            [0.380_365_25, -2.820_150_70, 23.178_821_35, 307.367_684_07],
+            [-2.294_347_33, 13.276_897_88, -14.268_289_04, 63.453_183_30],
+            328.106_719_97,
+        )
+    }
+
+    /// `a` and `b` for the ply, in centipawns
+    fn params(&self, ply: u32) -> (f64, f64) {
+        let m = ply.min(240) as f64 / 64.0;
+        let poly = |c: &[f64; 4]| ((c[0] * m + c[1]) * m + c[2]) * m + c[3];
+        let scale = 100.0 / self.pawn_value;
+
+        (poly(&self.a_coefs) * scale, poly(&self.b_coefs) * scale)
+    }
+
+    /// Probability of winning with a `cp` evaluation at `ply`
+    pub fn win_rate(&self, cp: i32, ply: u32) -> f64 {
+        let (a, b) = self.params(ply);
+        let cp = cp.clamp(-MAX_CP, MAX_CP) as f64;
+        1.0 / (1.0 + ((a - cp) / b).exp())
+    }
+
+    pub fn wdl(&self, cp: i32, ply: u32) -> Wdl {
+        let win = self.win_rate(cp, ply);
+        let loss = self.win_rate(-cp, ply);
+        Wdl::new(win, 1.0 - win - loss, loss)
+    }
+}
+
+impl Default for WdlModel {
+    fn default() -> Self {
+        Self::stockfish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_score() {
+        assert_eq!(cp_to_expected_score(0), 0.5);
+        assert!(cp_to_expected_score(300) > 0.7);
+        assert!((cp_to_expected_score(-300) + cp_to_expected_score(300) - 1.0).abs() < 1e-12);
+
+        for cp in [-900, -120, 0, 35, 500] {
+            assert_eq!(expected_score_to_cp(cp_to_expected_score(cp)), cp);
+        }
+        assert_eq!(expected_score_to_cp(1.0), MAX_CP);
+    }
+
+    #[test]
+    fn test_wdl() {
+        let model = WdlModel::default();
+
+        let even = model.wdl(0, 30);
+        assert_eq!(even.win, even.loss);
+        assert!(even.draw > 0.9);
+        assert!((even.expected_score() - 0.5).abs() < 1e-12);
+
+        // 100cp is won half of the time at move 32
+        assert!((model.win_rate(100, 64) - 0.5).abs() < 1e-6);
+
+        let up = model.wdl(250, 40);
+        assert!(up.win > up.draw && up.loss < 0.01);
+        let down = model.wdl(-250, 40);
+        assert_eq!((up.flip().win, up.flip().loss), (down.win, down.loss));
+        assert!((up.win + up.draw + up.loss - 1.0).abs() < 1e-12);
+    }
+}